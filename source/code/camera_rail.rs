@@ -0,0 +1,322 @@
+use bevy::{
+    ecs::{
+        component::Component,
+        entity::Entity,
+        query::{Added, Changed, With},
+        system::{Commands, Query},
+    },
+    gltf::GltfExtras,
+    math::{
+        cubic_splines::{
+            CubicBezier,
+            CubicCardinalSpline,
+            CubicCurve, CubicGenerator,
+        },
+        Vec3,
+    },
+    transform::components::GlobalTransform,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    cylinder_camera::{
+        DesiredTransformVariablesComponent,
+        ObservedEntityVariablesComponent,
+    },
+    math::find_nearest_parameter_on_curve,
+};
+
+/// number of samples for each segment of a spline, when projecting onto it.
+const CAMERA_RAIL_SUBDIVISIONS_PER_SEGMENT: usize = 16;
+
+/// spline of a camera rail, in the local space of the rail entity.
+#[derive(Clone, Serialize, Deserialize)]
+pub enum CameraRailSpline {
+    /// passes through all points but the first and last, which only shape the ends.
+    /// nb, needs at least four points.
+    CatmullRom(Vec<Vec3>),
+
+    /// cubic bezier segments.
+    /// ie, [start, start control, end control, end] for each segment.
+    Bezier(Vec<[Vec3; 4]>),
+}
+
+impl CameraRailSpline {
+    pub fn to_curve(&self) -> CubicCurve<Vec3> {
+        return match self {
+            CameraRailSpline::CatmullRom(
+                points,
+            ) => CubicCardinalSpline::new_catmull_rom(
+                points.clone(),
+            )
+            .to_curve(),
+            CameraRailSpline::Bezier(
+                segments,
+            ) => CubicBezier::new(
+                segments.clone(),
+            )
+            .to_curve(),
+        };
+    }
+}
+
+/// component for a rail entity which tracks the camera along a spline.
+/// ie, cinematic tracking shots for corridors and loops.
+#[derive(
+    Component,
+    Clone,
+    Serialize,
+    Deserialize,
+)]
+pub struct CameraRailComponent {
+    /// spline of the camera.
+    pub camera_spline: CameraRailSpline,
+
+    /// spline of the progress of the observed entity.
+    /// ie, progress along it is the same progress along the camera spline.
+    pub progress_spline: CameraRailSpline,
+
+    /// maximum distance of the observed entity from the progress spline to be on the rail, in m.
+    pub maximum_distance: f32,
+}
+
+/// component with the curves of the splines of a camera rail.
+/// ie, built from [CameraRailComponent] when it is added or changed, instead of each tick.
+#[derive(Component)]
+pub struct CameraRailCurvesComponent {
+    pub camera_curve: CubicCurve<Vec3>,
+    pub progress_curve: CubicCurve<Vec3>,
+}
+
+/// extras of a glTF node with a camera rail.
+/// ie, `{ "camera_rail": { ... } }`, with points in the local space of the node.
+#[derive(Deserialize)]
+struct CameraRailGltfExtras {
+    camera_rail: CameraRailComponent,
+}
+
+/// component with variables for the camera rail of a camera.
+#[derive(Component, Default)]
+pub struct CameraRailVariablesComponent
+{
+    /// rail the observed entity is on.
+    pub rail_entity: Option<Entity>,
+}
+
+/// global position on the camera spline of a camera rail, at the progress of a global position along the progress spline.
+/// also, the distance of the position from the progress spline.
+/// none when either spline has no segments.
+pub fn find_camera_rail_translation(
+    rail_global_transform: &GlobalTransform,
+    camera_rail_curves: &CameraRailCurvesComponent,
+    position: Vec3,
+) -> Option<(Vec3, f32)> {
+    let rail_affine =
+        rail_global_transform.affine();
+    let progress_curve =
+        &camera_rail_curves.progress_curve;
+    let camera_curve =
+        &camera_rail_curves.camera_curve;
+
+    if camera_curve.segments().is_empty() {
+        return None;
+    }
+
+    let progress_parameter =
+        find_nearest_parameter_on_curve(
+            progress_curve,
+            rail_affine
+                .inverse()
+                .transform_point3(position),
+            CAMERA_RAIL_SUBDIVISIONS_PER_SEGMENT,
+        )?;
+
+    let distance = rail_affine
+        .transform_point3(
+            progress_curve
+                .position(progress_parameter),
+        )
+        .distance(position);
+
+    // same progress, from 0 to 1, along both splines
+    let progress = progress_parameter
+        / progress_curve.segments().len()
+            as f32;
+
+    return Some((
+        rail_affine.transform_point3(
+            camera_curve.position(
+                progress
+                    * camera_curve
+                        .segments()
+                        .len()
+                        as f32,
+            ),
+        ),
+        distance,
+    ));
+}
+
+// REGION behavior component
+
+/// component for [set_desired_transform_translation_using_camera_rail_behavior_system].
+#[derive(Component, Default)]
+pub struct SetDesiredTransformTranslationUsingCameraRailBehaviorComponent;
+
+// REGIONEND
+
+// REGION behavior system
+
+/// set [DesiredTransformVariablesComponent] translation on the camera spline of the nearest camera rail, by projecting the observed entity onto its progress spline.
+/// ie, unchanged when the observed entity is on no rail.
+pub fn set_desired_transform_translation_using_camera_rail_behavior_system(
+    mut query: Query<
+        (
+            &ObservedEntityVariablesComponent,
+            &mut DesiredTransformVariablesComponent,
+            &mut CameraRailVariablesComponent,
+        ),
+        With<SetDesiredTransformTranslationUsingCameraRailBehaviorComponent>,
+    >,
+    observed_query: Query<&GlobalTransform>,
+    camera_rail_query: Query<(
+        Entity,
+        &GlobalTransform,
+        &CameraRailComponent,
+        &CameraRailCurvesComponent,
+    )>,
+) {
+    for (
+        observed_entity,
+        mut desired_transform_variables,
+        mut camera_rail_variables,
+    ) in query.iter_mut()
+    {
+        camera_rail_variables.rail_entity =
+            None;
+
+        let observed_result = observed_query
+            .get(observed_entity.entity);
+
+        if observed_result.is_err() {
+            continue;
+        }
+
+        let observed_position =
+            observed_result
+                .unwrap()
+                .translation();
+
+        // nearest rail, by distance to its progress spline
+        let mut nearest_distance =
+            f32::INFINITY;
+        let mut nearest_translation =
+            Vec3::ZERO;
+
+        for camera_rail in
+            camera_rail_query.iter()
+        {
+            let rail_translation_result =
+                find_camera_rail_translation(
+                    camera_rail.1,
+                    camera_rail.3,
+                    observed_position,
+                );
+
+            if rail_translation_result
+                .is_none()
+            {
+                continue;
+            }
+
+            let (rail_translation, distance) =
+                rail_translation_result
+                    .unwrap();
+
+            if distance
+                > camera_rail
+                    .2
+                    .maximum_distance
+                || distance >= nearest_distance
+            {
+                continue;
+            }
+
+            nearest_distance = distance;
+            nearest_translation =
+                rail_translation;
+
+            camera_rail_variables
+                .rail_entity =
+                Some(camera_rail.0);
+        }
+
+        if camera_rail_variables
+            .rail_entity
+            .is_none()
+        {
+            continue;
+        }
+
+        desired_transform_variables
+            .desired_transform
+            .translation =
+            nearest_translation;
+    }
+}
+
+// REGIONEND
+
+// REGION apply system
+
+/// system to build the curves of camera rails when they are added or changed.
+pub fn apply_camera_rail_curves_system(
+    mut commands: Commands,
+    query: Query<
+        (Entity, &CameraRailComponent),
+        Changed<CameraRailComponent>,
+    >,
+) {
+    for (entity, camera_rail) in
+        query.iter()
+    {
+        commands.entity(entity).insert(
+            CameraRailCurvesComponent {
+                camera_curve: camera_rail
+                    .camera_spline
+                    .to_curve(),
+                progress_curve: camera_rail
+                    .progress_spline
+                    .to_curve(),
+            },
+        );
+    }
+}
+
+/// system to make camera rails of glTF nodes with camera rail extras.
+/// ie, the node is an empty.
+pub fn apply_camera_rail_using_gltf_extras_system(
+    mut commands: Commands,
+    query: Query<
+        (Entity, &GltfExtras),
+        Added<GltfExtras>,
+    >,
+) {
+    for (entity, gltf_extras) in
+        query.iter()
+    {
+        let Ok(camera_rail_extras) =
+            serde_json::from_str::<
+                CameraRailGltfExtras,
+            >(&gltf_extras.value)
+        else {
+            continue;
+        };
+
+        commands
+            .entity(entity)
+            .insert(camera_rail_extras.camera_rail);
+    }
+}
+
+// REGIONEND
@@ -0,0 +1,429 @@
+use bevy::{
+    ecs::{
+        component::Component,
+        entity::Entity,
+        query::{Added, With},
+        system::{Commands, Query, Res},
+    },
+    gltf::GltfExtras,
+    math::Vec3,
+    time::Time,
+    transform::components::GlobalTransform,
+};
+use bevy_rapier3d::geometry::{
+    Collider, CollisionGroups, Group,
+    Sensor,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    camera_rail::{
+        find_camera_rail_translation,
+        CameraRailCurvesComponent,
+    },
+    cylinder_camera::{
+        CylinderCoordinatesForDesiredTransformTranslationVariablesComponent,
+        DesiredLookatVariablesComponent,
+        DesiredTransformVariablesComponent,
+        ObservedEntityVariablesComponent,
+        ParentTransformVariablesComponent,
+    },
+    math::{FromCylindrical, SmoothDamp},
+};
+
+/// position of the camera in a camera zone.
+#[derive(Clone, Serialize, Deserialize)]
+pub enum CameraZonePosition {
+    /// from the cylinder coordinates of the camera, with the overrides of the preset.
+    Cylinder,
+
+    /// fixed global position.
+    Fixed(Vec3),
+
+    /// position on the camera spline of a camera rail entity, at the progress of the observed entity.
+    /// ie, a rail/spline track while in the zone, regardless of the maximum distance of the rail.
+    /// nb, the cylinder position when the entity is not a camera rail.
+    Rail(Entity),
+}
+
+/// camera preset of a camera zone.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CameraZonePreset {
+    pub position: CameraZonePosition,
+
+    /// cylinder rotation, in radians, instead of the camera's own.
+    /// ie, locks the rotation.
+    pub cylinder_rotation: Option<f32>,
+
+    /// cylinder distance, instead of the camera's own.
+    pub cylinder_distance: Option<f32>,
+
+    /// cylinder height, instead of the camera's own.
+    pub cylinder_height: Option<f32>,
+
+    /// global lookat position, instead of the observed entity.
+    pub lookat_position: Option<Vec3>,
+}
+
+/// component for a zone entity which overrides the camera inside of its collider.
+/// nb, the collider should be a sensor which does not collide with anything.
+#[derive(
+    Component,
+    Clone,
+    Serialize,
+    Deserialize,
+)]
+pub struct CameraZoneComponent {
+    pub preset: CameraZonePreset,
+
+    /// zones with a higher priority override overlapping zones with a lower priority.
+    pub priority: i32,
+
+    /// approximate time to blend in and out of the preset, in seconds.
+    pub blend_time: f32,
+}
+
+/// extras of a glTF node with a camera zone.
+/// ie, `{ "camera_zone": { ... } }`.
+#[derive(Deserialize)]
+struct CameraZoneGltfExtras {
+    camera_zone: CameraZoneComponent,
+}
+
+/// blend of the preset of a camera zone into a camera.
+pub struct CameraZoneBlend {
+    pub zone_entity: Entity,
+
+    /// weight of the preset of the zone, from 0 to 1.
+    pub weight: f32,
+    pub weight_velocity: f32,
+}
+
+/// component with variables for the camera zones of a camera.
+#[derive(Component, Default)]
+pub struct CameraZoneVariablesComponent
+{
+    /// zones which are blending in or out, with the active zone last.
+    /// ie, each preset is blended over the presets before it, so switching zones blends the previous preset out while the next blends in.
+    pub blends: Vec<CameraZoneBlend>,
+}
+
+// REGION behavior component
+
+/// component for [set_desired_transform_using_camera_zone_behavior_system].
+#[derive(Component, Default)]
+pub struct SetDesiredTransformUsingCameraZoneBehaviorComponent;
+
+// REGIONEND
+
+// REGION behavior system
+
+/// set [DesiredTransformVariablesComponent] and [DesiredLookatVariablesComponent] using the camera zone the observed entity is in, with the highest priority.
+/// ie, blend into the preset of the zone when entering it, and out when leaving it.
+pub fn set_desired_transform_using_camera_zone_behavior_system(
+    time: Res<Time>,
+    mut query: Query<
+        (
+            &ObservedEntityVariablesComponent,
+            &ParentTransformVariablesComponent,
+            &CylinderCoordinatesForDesiredTransformTranslationVariablesComponent,
+            &mut DesiredTransformVariablesComponent,
+            &mut DesiredLookatVariablesComponent,
+            &mut CameraZoneVariablesComponent,
+        ),
+        With<SetDesiredTransformUsingCameraZoneBehaviorComponent>,
+    >,
+    observed_query: Query<&GlobalTransform>,
+    camera_zone_query: Query<(
+        Entity,
+        &GlobalTransform,
+        &Collider,
+        &CameraZoneComponent,
+    )>,
+    camera_rail_query: Query<(
+        &GlobalTransform,
+        &CameraRailCurvesComponent,
+    )>,
+) {
+    let delta_seconds =
+        time.delta_seconds();
+
+    if delta_seconds <= 0.0 {
+        return;
+    }
+
+    for (
+        observed_entity,
+        parent_transform,
+        cylinder_coordinates_for_desired_transform,
+        mut desired_transform_variables,
+        mut desired_lookat_variables,
+        mut camera_zone_variables,
+    ) in query.iter_mut()
+    {
+        let observed_result = observed_query
+            .get(observed_entity.entity);
+
+        if observed_result.is_err() {
+            continue;
+        }
+
+        let observed_position =
+            observed_result
+                .unwrap()
+                .translation();
+
+        let active_camera_zone_entity =
+            camera_zone_query
+                .iter()
+                .filter(|camera_zone| {
+                    let (
+                        _,
+                        zone_rotation,
+                        zone_translation,
+                    ) = camera_zone
+                        .1
+                        .to_scale_rotation_translation();
+
+                    return camera_zone
+                        .2
+                        .contains_point(
+                            zone_translation,
+                            zone_rotation,
+                            observed_position,
+                        );
+                })
+                .max_by_key(|camera_zone| {
+                    camera_zone.3.priority
+                })
+                .map(|camera_zone| {
+                    camera_zone.0
+                });
+
+        // the active zone blends over the others
+        if let Some(active_zone_entity) =
+            active_camera_zone_entity
+        {
+            let blend_index_result =
+                camera_zone_variables
+                    .blends
+                    .iter()
+                    .position(|blend| {
+                        blend.zone_entity
+                            == active_zone_entity
+                    });
+
+            let active_blend =
+                match blend_index_result {
+                    Some(blend_index) => {
+                        camera_zone_variables
+                            .blends
+                            .remove(blend_index)
+                    }
+                    None => CameraZoneBlend {
+                        zone_entity:
+                            active_zone_entity,
+                        weight: 0.0,
+                        weight_velocity: 0.0,
+                    },
+                };
+
+            camera_zone_variables
+                .blends
+                .push(active_blend);
+        }
+
+        // blend each zone in or out with its own blend time, and forget zones which blended out
+        camera_zone_variables
+            .blends
+            .retain_mut(|blend| {
+                let camera_zone_result =
+                    camera_zone_query
+                        .get(blend.zone_entity);
+
+                if camera_zone_result.is_err()
+                {
+                    return false;
+                }
+
+                let target_weight: f32 =
+                    if active_camera_zone_entity
+                        == Some(
+                            blend.zone_entity,
+                        )
+                    {
+                        1.0
+                    } else {
+                        0.0
+                    };
+
+                let (
+                    next_weight,
+                    next_weight_velocity,
+                ) = f32::smooth_damp(
+                    blend.weight,
+                    target_weight,
+                    blend.weight_velocity,
+                    camera_zone_result
+                        .unwrap()
+                        .3
+                        .blend_time,
+                    f32::INFINITY,
+                    delta_seconds,
+                );
+                blend.weight = f32::clamp(
+                    next_weight,
+                    0.0,
+                    1.0,
+                );
+                blend.weight_velocity =
+                    next_weight_velocity;
+
+                return target_weight > 0.0
+                    || blend.weight > 0.001;
+            });
+
+        for blend in
+            camera_zone_variables
+                .blends
+                .iter()
+        {
+            let camera_zone_result =
+                camera_zone_query
+                    .get(blend.zone_entity);
+
+            if camera_zone_result.is_err() {
+                continue;
+            }
+
+            let preset = &camera_zone_result
+                .unwrap()
+                .3
+                .preset;
+
+            let mut cylinder_coordinates =
+                cylinder_coordinates_for_desired_transform
+                    .cylinder_coordindates;
+            if let Some(cylinder_rotation) =
+                preset.cylinder_rotation
+            {
+                cylinder_coordinates
+                    .rotation =
+                    cylinder_rotation;
+            }
+
+            if let Some(cylinder_distance) =
+                preset.cylinder_distance
+            {
+                cylinder_coordinates
+                    .distance =
+                    cylinder_distance;
+            }
+
+            if let Some(cylinder_height) =
+                preset.cylinder_height
+            {
+                cylinder_coordinates.height =
+                    cylinder_height;
+            }
+
+            let cylinder_translation =
+                parent_transform
+                    .transform
+                    .compute_affine()
+                    .transform_point3(
+                        Vec3::from_cylindrical(
+                            &cylinder_coordinates,
+                        ),
+                    );
+
+            let zone_translation =
+                match &preset.position {
+                    CameraZonePosition::Cylinder => {
+                        cylinder_translation
+                    }
+                    CameraZonePosition::Fixed(
+                        fixed_position,
+                    ) => *fixed_position,
+                    CameraZonePosition::Rail(
+                        rail_entity,
+                    ) => camera_rail_query
+                        .get(*rail_entity)
+                        .ok()
+                        .and_then(|camera_rail| {
+                            find_camera_rail_translation(
+                                camera_rail.0,
+                                camera_rail.1,
+                                observed_position,
+                            )
+                        })
+                        .map(|(rail_translation, _)| {
+                            rail_translation
+                        })
+                        .unwrap_or(
+                            cylinder_translation,
+                        ),
+                };
+
+            desired_transform_variables
+                .desired_transform
+                .translation = Vec3::lerp(
+                desired_transform_variables
+                    .desired_transform
+                    .translation,
+                zone_translation,
+                blend.weight,
+            );
+
+            if let Some(lookat_position) =
+                preset.lookat_position
+            {
+                desired_lookat_variables
+                    .position = Vec3::lerp(
+                    desired_lookat_variables
+                        .position,
+                    lookat_position,
+                    blend.weight,
+                );
+            }
+        }
+    }
+}
+
+// REGIONEND
+
+// REGION apply system
+
+/// system to make camera zones of glTF nodes with camera zone extras.
+/// ie, the node is a cube empty, scaled to the size of the zone.
+pub fn apply_camera_zone_using_gltf_extras_system(
+    mut commands: Commands,
+    query: Query<
+        (Entity, &GltfExtras),
+        Added<GltfExtras>,
+    >,
+) {
+    for (entity, gltf_extras) in
+        query.iter()
+    {
+        let Ok(camera_zone_extras) =
+            serde_json::from_str::<
+                CameraZoneGltfExtras,
+            >(&gltf_extras.value)
+        else {
+            continue;
+        };
+
+        commands.entity(entity).insert((
+            camera_zone_extras.camera_zone,
+            Collider::cuboid(1.0, 1.0, 1.0),
+            Sensor,
+            CollisionGroups::new(
+                Group::NONE,
+                Group::NONE,
+            ),
+        ));
+    }
+}
+
+// REGIONEND
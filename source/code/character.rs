@@ -3,23 +3,30 @@ use bevy::{
         bundle::Bundle,
         component::Component,
         entity::Entity,
+        event::{
+            Event, EventReader,
+            EventWriter,
+        },
         query::{With, Without},
         system::{
             Commands, Query, Res,
         },
     },
     hierarchy::Children,
+    log::debug,
     math::{
         Affine3A, Quat, Vec2, Vec3,
         Vec3Swizzles,
     },
     render::view::InheritedVisibility,
+    time::Time,
     transform::components::{
         GlobalTransform, Transform,
     },
 };
 use bevy_rapier3d::{
     dynamics::Velocity,
+    rapier::prelude::FeatureId,
     geometry::{
         Collider, CollisionGroups,
         Group,
@@ -28,7 +35,23 @@ use bevy_rapier3d::{
     plugin::RapierContext,
 };
 
-use crate::math::MoveTowards;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    character_definition::CharacterDefinitionVariablesComponent,
+    character_move_shape::{
+        move_character_body,
+        CharacterMoveShapeParametersComponent,
+        CharacterMoveShapeVariablesComponent,
+        MoveShapeHit,
+    },
+    character_phase::{
+        CharacterPhase,
+        CharacterPhaseVariablesComponent,
+    },
+    gravity::CharacterGravityVariablesComponent,
+    math::MoveTowards,
+};
 
 #[derive(Component)]
 pub struct CharacterTagComponent;
@@ -73,15 +96,312 @@ pub struct CharacterMovementVariablesComponent
 }
 
 /// component with parameters for movement for a character.
-#[derive(Component)]
+#[derive(
+    Component,
+    Clone,
+    Serialize,
+    Deserialize,
+)]
 pub struct CharacterMovementParametersComponent
 {
-    /// amount to acclerate updwards towards desired velocity each update.
+    /// horizontal speed while movement input is fully tilted, in m/s.
+    pub desired_horizontal_speed: f32,
+
+    /// amount to acclerate updwards towards desired velocity, in m/s².
     pub global_horizontal_acceleration:
         f32,
 
-    /// amount to accelerate towards zero each update while there is no input.
+    /// amount to accelerate towards zero while there is no input, in m/s².
     pub global_horizontal_drag: f32,
+
+    /// vertical speed added when jumping, in m/s.
+    pub jump_speed: f32,
+}
+
+/// component with parameters for movement on a slope of the stage for a character.
+#[derive(
+    Component,
+    Clone,
+    Serialize,
+    Deserialize,
+)]
+pub struct CharacterSlopeMovementParametersComponent
+{
+    /// amount to accelerate downhill on a vertical slope, in m/s².
+    /// ie, gravity projected onto the stage.
+    pub downhill_acceleration: f32,
+
+    /// amount to increase desired speed while running downhill on a vertical slope, in m/s.
+    pub downhill_desired_speed_increase:
+        f32,
+
+    /// fraction of acceleration lost while running uphill, on a vertical slope.
+    pub uphill_acceleration_resistance:
+        f32,
+
+    /// minimum angle of slope, in radians, to slide down while there is no input.
+    pub minimum_slide_angle: f32,
+}
+
+/// component with parameters for running into walls for a character.
+#[derive(
+    Component,
+    Clone,
+    Serialize,
+    Deserialize,
+)]
+pub struct CharacterBonkParametersComponent
+{
+    /// minimum speed into a wall to bonk, in m/s.
+    /// slower characters only have their movement into the wall cancelled.
+    pub minimum_bonk_speed: f32,
+
+    /// fraction of the speed into the wall to rebound with when bonking.
+    pub bonk_rebound_speed_scale: f32,
+
+    /// time to lock out input after bonking, in seconds.
+    pub bonk_duration: f32,
+
+    /// minimum angle, in radians, between the character's up and a surface normal for the surface to be a wall.
+    pub minimum_wall_angle: f32,
+
+    /// distance ahead of the body to detect walls, in addition to the distance moved this update, in m.
+    pub wall_contact_distance: f32,
+}
+
+/// component with parameters for stepping up onto small ledges for a character.
+#[derive(
+    Component,
+    Clone,
+    Serialize,
+    Deserialize,
+)]
+pub struct CharacterStepParametersComponent
+{
+    /// maximum height of a ledge to step up onto, in m.
+    pub maximum_step_height: f32,
+
+    /// distance past the ledge to look for the top of the step, in m.
+    pub step_forward_probe_distance: f32,
+
+    /// height above the feet to look for steps from, in m.
+    /// ie, lower steps are walked onto by snapping to the ground.
+    pub step_probe_height: f32,
+}
+
+/// component with parameters for getting up onto ledges from the air for a character.
+#[derive(
+    Component,
+    Clone,
+    Serialize,
+    Deserialize,
+)]
+pub struct CharacterLedgeGetupParametersComponent
+{
+    /// maximum height of the top of a ledge above the hips to reach, in m.
+    pub maximum_reach_height_above_hips:
+        f32,
+
+    /// maximum depth of the top of a ledge below the hips to reach, in m.
+    pub maximum_reach_depth_below_hips:
+        f32,
+
+    /// distance ahead of the body to detect ledges, in m.
+    pub ledge_contact_distance: f32,
+
+    /// distance past the edge of the ledge to look for the top of the ledge, in m.
+    pub ledge_forward_probe_distance: f32,
+
+    /// time to pull up onto the ledge, in seconds.
+    pub pull_up_duration: f32,
+}
+
+/// component for ledge getup state of a character.
+/// ie, the character is hanging from or pulling up onto a ledge.
+#[derive(Component)]
+pub struct CharacterLedgeGetupComponent {
+    pub ledge_entity: Entity,
+
+    /// position of the character when it caught the ledge.
+    pub hang_position: Vec3,
+
+    /// position on top of the ledge to place the character.
+    pub ledge_top_position: Vec3,
+
+    /// up against gravity when the character caught the ledge.
+    pub up: Vec3,
+}
+
+/// component with parameters for vaulting over low obstacles for a character.
+#[derive(
+    Component,
+    Clone,
+    Serialize,
+    Deserialize,
+)]
+pub struct CharacterVaultParametersComponent
+{
+    /// maximum height of the top of an obstacle to vault over, in m.
+    /// obstacles lower than the maximum step height are stepped onto instead.
+    pub maximum_vault_height: f32,
+
+    /// maximum depth of an obstacle to vault over, in m.
+    pub maximum_vault_depth: f32,
+
+    /// minimum speed into an obstacle to vault over it, in m/s.
+    pub minimum_vault_speed: f32,
+
+    /// height of the feet above the top of the obstacle at the peak of the vault, in m.
+    pub vault_clearance_height: f32,
+
+    /// distance past the far side of the obstacle to land, in m.
+    pub vault_landing_distance: f32,
+}
+
+/// component for vault state of a character.
+/// ie, the character is vaulting over an obstacle.
+#[derive(Component)]
+pub struct CharacterVaultComponent {
+    /// position of the character when it started vaulting.
+    pub start_position: Vec3,
+
+    /// position past the obstacle to land.
+    pub end_position: Vec3,
+
+    /// height of the peak of the vault, above the line from start to end, in m.
+    pub arc_height: f32,
+
+    /// up against gravity when the character started vaulting.
+    /// ie, the direction of the peak of the arc.
+    pub up: Vec3,
+
+    /// time to vault over the obstacle, in seconds.
+    pub vault_duration: f32,
+
+    /// global horizontal velocity before vaulting, restored after vaulting.
+    pub global_horizontal_velocity: Vec2,
+}
+
+/// when a character catches the edge of the stage, instead of leaving it.
+#[derive(
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Debug,
+    Serialize,
+    Deserialize,
+)]
+pub enum CharacterLedgeCatchMode {
+    /// always stop at the edge of the stage.
+    AlwaysCatch,
+    /// stop at the edge of the stage while moving no faster than walking speed.
+    CatchWhileWalking,
+    /// always leave the stage.
+    /// nb, also never trip on the edge of the stage.
+    Never,
+}
+
+/// component with parameters for catching the edge of the stage for a character.
+#[derive(
+    Component,
+    Clone,
+    Serialize,
+    Deserialize,
+)]
+pub struct CharacterLedgeCatchParametersComponent
+{
+    pub mode: CharacterLedgeCatchMode,
+
+    /// maximum speed for [CharacterLedgeCatchMode::CatchWhileWalking], in m/s.
+    pub maximum_walking_speed: f32,
+
+    /// distance inside the edge to place a character which caught it, in m.
+    pub ledge_catch_margin: f32,
+}
+
+/// component with parameters for tripping on the edge of the stage for a character.
+#[derive(
+    Component,
+    Clone,
+    Serialize,
+    Deserialize,
+)]
+pub struct CharacterLedgeTripParametersComponent
+{
+    /// maximum speed to trip on the edge of the stage, in m/s.
+    /// faster characters leave the stage, unless they catch the edge.
+    pub maximum_trip_speed: f32,
+
+    /// time to teeter on the edge before falling, in seconds.
+    pub teeter_duration: f32,
+
+    /// speed away from the edge when falling after teetering, in m/s.
+    pub trip_fall_speed: f32,
+}
+
+/// component for ledge trip state of a character.
+/// ie, the character is teetering on the edge of the stage.
+#[derive(Component)]
+pub struct CharacterLedgeTripComponent {
+    /// global point on the edge of the stage.
+    pub edge_point: Vec3,
+
+    /// global normal of the edge of the stage, pointing away from the stage.
+    pub edge_normal: Vec3,
+}
+
+/// component with parameters for running along walls for a character.
+#[derive(
+    Component,
+    Clone,
+    Serialize,
+    Deserialize,
+)]
+pub struct CharacterWallRunParametersComponent
+{
+    /// minimum angle, in radians, between global up and a surface normal to run along the surface.
+    /// ie, how near-vertical a wall must be.
+    pub minimum_wall_run_angle: f32,
+
+    /// minimum speed into a wall to start running along it, in m/s.
+    pub minimum_entry_speed: f32,
+
+    /// minimum speed to keep running along a wall, in m/s.
+    pub minimum_wall_run_speed: f32,
+
+    /// maximum time to run along a wall, in seconds.
+    pub maximum_wall_run_duration: f32,
+
+    /// speed away from the wall when jumping off it, in m/s.
+    pub wall_jump_speed: f32,
+
+    /// upwards speed when jumping off a wall, in m/s.
+    pub wall_jump_up_speed: f32,
+}
+
+/// component with parameters for the orientation of a character while in the air.
+#[derive(
+    Component,
+    Clone,
+    Serialize,
+    Deserialize,
+)]
+pub struct CharacterAirOrientationParametersComponent
+{
+    /// maximum speed to turn up towards the inverse of gravity while in the air, in rad/s.
+    pub air_rotation_speed: f32,
+}
+
+/// event for a character bonking into a wall.
+#[derive(Event)]
+pub struct CharacterBonkEvent {
+    pub character_entity: Entity,
+    pub wall_entity: Entity,
+    /// speed into the wall, in m/s.
+    pub impact_speed: f32,
+    /// global normal of the wall.
+    pub wall_normal: Vec3,
 }
 
 /// component for stage state of a character.
@@ -91,12 +411,19 @@ pub struct CharacterStageComponent {
 }
 
 /// parameters for the "fall" phase of a character.
-#[derive(Component)]
+#[derive(
+    Component,
+    Clone,
+    Serialize,
+    Deserialize,
+)]
 pub struct CharacterFallPhaseMovementParametersComponent
 {
+    /// in m/s.
     pub maximum_down_speed: f32,
+    /// in m/s.
     pub maximum_up_speed: f32,
-    /// aka "gravity"
+    /// aka "gravity", in m/s².
     pub down_acceleration: f32,
 }
 
@@ -112,70 +439,186 @@ pub struct CharacterBundle {
     pub fall_phase_movement_parameters: CharacterFallPhaseMovementParametersComponent,
     pub movement_variables: CharacterMovementVariablesComponent,
     pub movement_parameters: CharacterMovementParametersComponent,
+    pub slope_movement_parameters: CharacterSlopeMovementParametersComponent,
+    pub bonk_parameters: CharacterBonkParametersComponent,
+    pub step_parameters: CharacterStepParametersComponent,
+    pub ledge_getup_parameters:
+        CharacterLedgeGetupParametersComponent,
+    pub vault_parameters:
+        CharacterVaultParametersComponent,
+    pub ledge_catch_parameters:
+        CharacterLedgeCatchParametersComponent,
+    pub ledge_trip_parameters:
+        CharacterLedgeTripParametersComponent,
+    pub wall_run_parameters:
+        CharacterWallRunParametersComponent,
+    pub air_orientation_parameters:
+        CharacterAirOrientationParametersComponent,
+    pub gravity_variables:
+        CharacterGravityVariablesComponent,
+    pub move_shape_parameters:
+        CharacterMoveShapeParametersComponent,
+    pub move_shape_variables:
+        CharacterMoveShapeVariablesComponent,
+    pub phase_variables: CharacterPhaseVariablesComponent,
+    pub definition_variables:
+        CharacterDefinitionVariablesComponent,
 }
 
 /// system to update movement body velocity of a character
 pub fn update_character_horizontal_movement_velocity_system(
+    time: Res<Time>,
     mut character_query: Query<
         (
             &Transform,
             &CharacterPlayerInputComponent,
             &CharacterMovementParametersComponent,
             &mut CharacterMovementVariablesComponent,
+            &CharacterSlopeMovementParametersComponent,
+            Option<&CharacterStageComponent>,
+            &CharacterPhaseVariablesComponent,
+            &CharacterGravityVariablesComponent,
         ),
         With<CharacterTagComponent>,
     >,
 ) {
-    let character_result =
-        character_query
-            .get_single_mut();
-
-    if character_result.is_err() {
-        return;
-    }
-
-    let mut character =
-        character_result.unwrap();
+    for mut character in
+        character_query.iter_mut()
+    {
+        // TODO optimize this by going camera up to character up
+
+        // in the air, horizontal velocity is global regardless of orientation
+        let rotation_from_character_up_to_global_up =
+            if character.5.is_some() {
+                Quat::from_rotation_arc(
+                    *character.0.up(),
+                    Vec3::Y,
+                )
+            } else {
+                Quat::IDENTITY
+            };
+
+        // slope of the stage, on the character's local xz plane
+        // ie, gravity projected onto the stage
+        let mut downhill_direction =
+            Vec2::ZERO;
+        let mut slope_sine: f32 = 0.0;
+        if character.5.is_some() {
+            let global_downhill = Vec3::reject_from_normalized(
+                character.7.direction,
+                *character.0.up(),
+            );
 
-    // TODO optimize this by going camera up to character up
+            slope_sine =
+                global_downhill.length();
+            downhill_direction = Quat::mul_vec3(
+                rotation_from_character_up_to_global_up,
+                global_downhill,
+            )
+            .xz()
+            .normalize_or_zero();
+        }
 
-    let rotation_from_character_up_to_global_up =
-        Quat::from_rotation_arc(
-            *character.0.up(),
-            Vec3::Y,
-        );
+        // input is locked out while bonking
+        let desired_velocity =
+            if character.6.phase
+                == CharacterPhase::Bonk
+            {
+                Vec3::ZERO
+            } else {
+                character
+                    .1
+                    .global_movement_player_input
+                    * character
+                        .2
+                        .desired_horizontal_speed
+            };
+        let desired_velocity_magnitude =
+            desired_velocity
+                .length_squared();
+
+        let mut desired_global_velocity =
+            Quat::mul_vec3(rotation_from_character_up_to_global_up, desired_velocity).xz();
+
+        let mut acceleration: f32 =
+            if desired_velocity_magnitude
+                > 0.0
+            {
+                character.2.global_horizontal_acceleration
+            } else {
+                character
+                    .2
+                    .global_horizontal_drag
+            } * time.delta_seconds();
+
+        let is_sliding = desired_velocity_magnitude
+            <= 0.0
+            && slope_sine
+                >= f32::sin(
+                    character
+                        .4
+                        .minimum_slide_angle,
+                );
+
+        if desired_velocity_magnitude
+            > 0.0
+        {
+            // positive while running downhill, negative while running uphill
+            let downhill_alignment = Vec2::dot(
+                desired_global_velocity
+                    .normalize_or_zero(),
+                downhill_direction,
+            ) * slope_sine;
+
+            if downhill_alignment > 0.0 {
+                desired_global_velocity +=
+                    desired_global_velocity
+                        .normalize_or_zero()
+                        * character
+                            .4
+                            .downhill_desired_speed_increase
+                        * downhill_alignment;
+            } else {
+                acceleration *= 1.0
+                    + character
+                        .4
+                        .uphill_acceleration_resistance
+                        * downhill_alignment;
+            }
+        }
 
-    let desired_velocity = character
-        .1
-        .global_movement_player_input
-        * 8.0;
-    let desired_velocity_magnitude =
-        desired_velocity
-            .length_squared();
+        let mut next_global_velocity =
+            if is_sliding {
+                character
+                    .3
+                    .global_horizontal_velocity
+            } else {
+                Vec2::move_towards(
+                    character.3.global_horizontal_velocity,
+                    desired_global_velocity,
+                    acceleration,
+                )
+            };
+
+        // stand still on shallow slopes while there is no input
+        if desired_velocity_magnitude
+            > 0.0
+            || is_sliding
+        {
+            next_global_velocity +=
+                downhill_direction
+                    * character
+                        .4
+                        .downhill_acceleration
+                    * slope_sine
+                    * time.delta_seconds();
+        }
 
-    let acceleration: f32;
-    if desired_velocity_magnitude > 0.0
-    {
-        acceleration = character.2.global_horizontal_acceleration;
-    } else {
-        acceleration = character
-            .2
-            .global_horizontal_drag;
+        character
+            .3
+            .global_horizontal_velocity =
+            next_global_velocity;
     }
-
-    let desired_global_velocity =
-        Quat::mul_vec3(rotation_from_character_up_to_global_up, desired_velocity).xz();
-
-    let next_global_velocity = Vec2::move_towards(
-        character.3.global_horizontal_velocity,
-        desired_global_velocity,
-        acceleration,
-    );
-
-    character
-        .3
-        .global_horizontal_velocity =
-        next_global_velocity;
 }
 
 /// system to update movement body velocity of a character which is on the stage
@@ -186,315 +629,1415 @@ pub fn update_character_movement_velocity_while_on_stage_system(
             &CharacterPlayerInputComponent,
             &CharacterMovementParametersComponent,
             &mut CharacterMovementVariablesComponent,
+            &CharacterPhaseVariablesComponent,
+            &CharacterWallRunParametersComponent,
         ),
         (With<CharacterTagComponent>, With<CharacterStageComponent>),
     >,
 ) {
-    let character_result =
-        character_query
-            .get_single_mut();
+    for mut character in
+        character_query.iter_mut()
+    {
+        // input is locked out while bonking
+        if character.4.phase
+            == CharacterPhase::Bonk
+        {
+            continue;
+        }
 
-    if character_result.is_err() {
-        return;
+        if character
+            .1
+            .do_activate_jump_input
+        {
+            character
+                .3
+                .local_vertical_velocity +=
+                if character.4.phase
+                    == CharacterPhase::WallRun
+                {
+                    character
+                        .5
+                        .wall_jump_speed
+                } else {
+                    character.2.jump_speed
+                };
+        }
     }
+}
 
-    let mut character =
-        character_result.unwrap();
+/// find the top of a step in front of a character, from the point where a probe from the feet touches it.
+/// ie, there is a walkable surface lower than the maximum step height, with room for the body above it.
+#[allow(clippy::too_many_arguments)]
+fn find_step_top(
+    rapier_context: &RapierContext,
+    character_transform: &Transform,
+    body_translation: Vec3,
+    body_rotation: Quat,
+    body_collider: &Collider,
+    step_contact_point: Vec3,
+    step_parameters: &CharacterStepParametersComponent,
+    minimum_wall_angle: f32,
+) -> Option<Vec3> {
+    let character_up =
+        *character_transform.up();
+    let character_feet_position =
+        character_transform.translation;
+
+    let step_contact_height = Vec3::dot(
+        step_contact_point
+            - character_feet_position,
+        character_up,
+    );
 
-    if character
-        .1
-        .do_activate_jump_input
+    if step_contact_height
+        > step_parameters
+            .maximum_step_height
     {
-        character
-            .3
-            .local_vertical_velocity +=
-            12.0;
+        return None;
     }
-}
 
-/// system to update movement velocity of a character which is in the air
-pub fn update_character_movement_velocity_while_in_air_phase_system(
-    mut character_query: Query<
-        (
-            &CharacterFallPhaseMovementParametersComponent,
-            &mut CharacterMovementVariablesComponent,
-        ),
-        (
-            With<CharacterTagComponent>,
-            Without<CharacterStageComponent>,
-        ),
-    >,
-) {
-    let character_result =
-        character_query
-            .get_single_mut();
+    let forward = Vec3::reject_from_normalized(
+        step_contact_point
+            - character_feet_position,
+        character_up,
+    )
+    .normalize_or_zero();
 
-    if character_result.is_err() {
-        return;
+    if forward == Vec3::ZERO {
+        return None;
     }
 
-    let mut character =
-        character_result.unwrap();
-
-    character
-        .1
-        .local_vertical_velocity =
-        f32::clamp(
-            character
-                .1
-                .local_vertical_velocity
-                - character
-                    .0
-                    .down_acceleration,
-            -character
-                .0
-                .maximum_down_speed,
-            character
-                .0
-                .maximum_up_speed,
+    let stage_query_filter =
+        QueryFilter::new().groups(
+            CollisionGroups::new(
+                Group::from_bits(0b0100)
+                    .unwrap(),
+                Group::from_bits(0b0010)
+                    .unwrap(),
+            ),
         );
+
+    // down onto the step, from above the maximum step height
+    let step_probe_position =
+        step_contact_point
+            - character_up
+                * step_contact_height
+            + forward
+                * step_parameters
+                    .step_forward_probe_distance
+            + character_up
+                * step_parameters
+                    .maximum_step_height;
+
+    let (_, step_hit) = rapier_context
+        .cast_ray_and_get_normal(
+            step_probe_position,
+            -character_up,
+            step_parameters
+                .maximum_step_height,
+            true,
+            stage_query_filter,
+        )?;
+
+    // inside geometry taller than a step
+    if step_hit.toi <= 0.0 {
+        return None;
+    }
+
+    if Vec3::angle_between(
+        step_hit.normal,
+        character_up,
+    ) >= minimum_wall_angle
+    {
+        return None;
+    }
+
+    let step_height = Vec3::dot(
+        step_hit.point
+            - character_feet_position,
+        character_up,
+    );
+
+    // room for the body, raised onto the step
+    let step_distance = Vec3::dot(
+        step_hit.point
+            - character_feet_position,
+        forward,
+    );
+
+    if rapier_context
+        .cast_shape(
+            body_translation
+                + character_up * step_height,
+            body_rotation,
+            forward,
+            body_collider,
+            step_distance,
+            true,
+            stage_query_filter,
+        )
+        .is_some()
+    {
+        return None;
+    }
+
+    return Some(step_hit.point);
 }
 
-/// system to update physics body velocity for a character using movement velocity
-pub fn update_character_body_velocity_while_on_stage_using_movement_velocity_system(
-    mut character_query: Query<
-        (
-            &Transform,
-            &CharacterPlayerInputComponent,
-            &CharacterTransformationFromPlayerToCameraVariablesComponent,
-            &CharacterMovementVariablesComponent,
-            &mut Velocity,
-        ),
-        (With<CharacterTagComponent>, With<CharacterStageComponent>),
-    >,
-) {
-    let character_result =
-        character_query
-            .get_single_mut();
+/// find the position past an obstacle in front of a character to vault to, and the height of the obstacle.
+/// ie, the top is higher than a step but within vault height, and the obstacle is thin enough.
+#[allow(clippy::too_many_arguments)]
+fn find_vault_landing(
+    rapier_context: &RapierContext,
+    character_transform: &Transform,
+    body_translation: Vec3,
+    body_collider: &Collider,
+    obstacle_contact_point: Vec3,
+    vault_parameters: &CharacterVaultParametersComponent,
+    step_parameters: &CharacterStepParametersComponent,
+    minimum_wall_angle: f32,
+) -> Option<(f32, Vec3)> {
+    let character_up =
+        *character_transform.up();
+    let character_feet_position =
+        character_transform.translation;
+
+    let forward = Vec3::reject_from_normalized(
+        obstacle_contact_point
+            - character_feet_position,
+        character_up,
+    )
+    .normalize_or_zero();
+
+    if forward == Vec3::ZERO {
+        return None;
+    }
+
+    let obstacle_contact_point_at_feet =
+        obstacle_contact_point
+            - character_up
+                * Vec3::dot(
+                    obstacle_contact_point
+                        - character_feet_position,
+                    character_up,
+                );
+
+    let stage_query_filter =
+        QueryFilter::new().groups(
+            CollisionGroups::new(
+                Group::from_bits(0b0100)
+                    .unwrap(),
+                Group::from_bits(0b0010)
+                    .unwrap(),
+            ),
+        );
+
+    // down onto the top, from the maximum vault height to the maximum step height
+    let (_, obstacle_top_hit) = rapier_context
+        .cast_ray_and_get_normal(
+            obstacle_contact_point_at_feet
+                + forward
+                    * step_parameters
+                        .step_forward_probe_distance
+                + character_up
+                    * vault_parameters
+                        .maximum_vault_height,
+            -character_up,
+            vault_parameters
+                .maximum_vault_height
+                - step_parameters
+                    .maximum_step_height,
+            true,
+            stage_query_filter,
+        )?;
+
+    // inside geometry taller than a vault
+    if obstacle_top_hit.toi <= 0.0 {
+        return None;
+    }
+
+    if Vec3::angle_between(
+        obstacle_top_hit.normal,
+        character_up,
+    ) >= minimum_wall_angle
+    {
+        return None;
+    }
+
+    let obstacle_height = Vec3::dot(
+        obstacle_top_hit.point
+            - character_feet_position,
+        character_up,
+    );
+
+    // back towards the far side, from the maximum vault depth, halfway up the obstacle
+    let (_, obstacle_far_side_toi) = rapier_context
+        .cast_ray(
+            obstacle_contact_point_at_feet
+                + forward
+                    * vault_parameters
+                        .maximum_vault_depth
+                + character_up
+                    * obstacle_height
+                    * 0.5,
+            -forward,
+            vault_parameters
+                .maximum_vault_depth,
+            true,
+            stage_query_filter,
+        )?;
+
+    // inside geometry deeper than a vault
+    if obstacle_far_side_toi <= 0.0 {
+        return None;
+    }
 
-    if character_result.is_err() {
-        return;
+    let obstacle_depth = vault_parameters
+        .maximum_vault_depth
+        - obstacle_far_side_toi;
+    let vault_landing_position =
+        obstacle_contact_point_at_feet
+            + forward
+                * (obstacle_depth
+                    + vault_parameters
+                        .vault_landing_distance);
+
+    // room for the body past the obstacle
+    if rapier_context
+        .intersection_with_shape(
+            vault_landing_position
+                + (body_translation
+                    - character_feet_position),
+            Quat::IDENTITY,
+            body_collider,
+            stage_query_filter,
+        )
+        .is_some()
+    {
+        return None;
     }
 
-    let mut character =
-        character_result.unwrap();
+    return Some((
+        obstacle_height,
+        vault_landing_position,
+    ));
+}
 
-    let rotation_from_global_up_to_character_up =
+/// global velocity of a character on the stage.
+/// ie, movement velocity from the character's local frame to global.
+fn find_global_velocity_while_on_stage(
+    character_up: Vec3,
+    movement_variables: &CharacterMovementVariablesComponent,
+) -> Vec3 {
+    return Quat::mul_vec3(
         Quat::from_rotation_arc(
             Vec3::Y,
-            *character.0.up(),
-        );
-    let next_body_global_horizontal_velocity = Quat::mul_vec3(
-        rotation_from_global_up_to_character_up,
+            character_up,
+        ),
         Vec3::new(
-            character.3.global_horizontal_velocity.x,
+            movement_variables
+                .global_horizontal_velocity
+                .x,
             0.0,
-            character.3.global_horizontal_velocity.y,
+            movement_variables
+                .global_horizontal_velocity
+                .y,
         ),
+    ) + character_up
+        * movement_variables
+            .local_vertical_velocity;
+}
+
+/// up of a character against gravity.
+/// ie, the up of the character when there is no gravity.
+fn find_gravity_up(
+    character_transform: &Transform,
+    gravity_variables: &CharacterGravityVariablesComponent,
+) -> Vec3 {
+    if gravity_variables.direction
+        == Vec3::ZERO
+    {
+        return *character_transform.up();
+    }
+
+    return -gravity_variables.direction;
+}
+
+/// if a surface is near-vertical enough to run along.
+/// ie, relative to the inverse of gravity.
+fn is_wall_runnable(
+    surface_normal: Vec3,
+    gravity_direction: Vec3,
+    wall_run_parameters: &CharacterWallRunParametersComponent,
+) -> bool {
+    let surface_angle = Vec3::angle_between(
+        surface_normal,
+        -gravity_direction,
     );
-    let next_body_global_vertical_velocity =
-        character.0.up()
-            * character
-                .3
-                .local_vertical_velocity;
-    character.4.linvel = next_body_global_horizontal_velocity + next_body_global_vertical_velocity;
+
+    return surface_angle
+        >= wall_run_parameters
+            .minimum_wall_run_angle
+        && surface_angle
+            <= std::f32::consts::PI
+                - wall_run_parameters
+                    .minimum_wall_run_angle;
 }
 
-/// system to update physics body velocity for a character using movement velocity
-pub fn update_character_body_velocity_while_in_air_using_movement_velocity_system(
-    mut character_query: Query<
-        (
-            &Transform,
-            &CharacterPlayerInputComponent,
-            &CharacterTransformationFromPlayerToCameraVariablesComponent,
-            &CharacterMovementVariablesComponent,
-            &mut Velocity,
-        ),
-        (
-            With<CharacterTagComponent>,
-            Without<CharacterStageComponent>,
-        ),
-    >,
+/// stick a character to a wall, with its up along the wall normal.
+/// ie, the character is on the wall as stage, and keeps its velocity along the wall.
+#[allow(clippy::too_many_arguments)]
+fn stick_character_to_wall(
+    commands: &mut Commands,
+    character_entity: Entity,
+    character_transform: &mut Transform,
+    movement_variables: &mut CharacterMovementVariablesComponent,
+    global_velocity: Vec3,
+    wall_entity: Entity,
+    wall_point: Vec3,
+    wall_normal: Vec3,
 ) {
-    let character_result =
-        character_query
-            .get_single_mut();
-
-    if character_result.is_err() {
-        return;
-    }
+    character_transform.rotation =
+        Quat::from_rotation_arc(
+            Vec3::Y,
+            wall_normal,
+        );
+    character_transform.translation =
+        wall_point;
 
-    let mut character =
-        character_result.unwrap();
+    movement_variables
+        .global_horizontal_velocity =
+        Quat::mul_vec3(
+            Quat::from_rotation_arc(
+                wall_normal,
+                Vec3::Y,
+            ),
+            global_velocity,
+        )
+        .xz();
+    movement_variables
+        .local_vertical_velocity = 0.0;
 
-    character.4.linvel = Vec3::new(
-        character
-            .3
-            .global_horizontal_velocity
-            .x,
-        character
-            .3
-            .local_vertical_velocity,
-        character
-            .3
-            .global_horizontal_velocity
-            .y,
-    )
+    commands
+        .entity(character_entity)
+        .insert(CharacterStageComponent {
+            stage_entity: wall_entity,
+        });
 }
 
-/// system to update a character body and stage status while on stage
-pub fn update_character_body_while_on_stage_system(
+/// system to cancel movement velocity of a character into a wall while on stage.
+/// ie, bonk when the speed into the wall is fast enough, step up when the wall is low enough, vault when the wall is low and thin enough, or run along the wall when it is near-vertical.
+pub fn update_character_movement_velocity_against_wall_while_on_stage_system(
+    time: Res<Time>,
     rapier_context: Res<RapierContext>,
     mut commands: Commands,
+    mut bonk_events: EventWriter<
+        CharacterBonkEvent,
+    >,
     mut character_query: Query<
         (
             Entity,
             &Children,
-            &Velocity,
             &mut Transform,
+            &CharacterBonkParametersComponent,
+            &mut CharacterMovementVariablesComponent,
+            &mut CharacterPhaseVariablesComponent,
+            &CharacterStepParametersComponent,
+            &CharacterVaultParametersComponent,
+            &CharacterWallRunParametersComponent,
+            &CharacterGravityVariablesComponent,
+            &CharacterMoveShapeParametersComponent,
         ),
         (
             With<CharacterTagComponent>,
-            With<
-                CharacterStageComponent,
-            >,
+            With<CharacterStageComponent>,
         ),
     >,
     character_body_query: Query<
-        (&Transform, &GlobalTransform, &Collider),
         (
-            With<CharacterBodyTagComponent>,
-            Without<CharacterTagComponent>,
-        ),
+            Entity,
+            &Transform,
+            &GlobalTransform,
+            &Collider,
+        ),
+        (
+            With<CharacterBodyTagComponent>,
+            Without<CharacterTagComponent>,
+        ),
     >,
 ) {
-    let character_result =
-        character_query
-            .get_single_mut();
+    for mut character in
+        character_query.iter_mut()
+    {
+        if character.5.phase
+            == CharacterPhase::Bonk
+            || character
+                .5
+                .next_phase
+                .is_some()
+        {
+            continue;
+        }
 
-    if character_result.is_err() {
-        return;
-    }
+        let character_body_result = character
+            .1
+            .iter()
+            .find_map(|child| {
+                character_body_query
+                    .get(*child)
+                    .ok()
+            });
+
+        if character_body_result.is_none() {
+            continue;
+        }
 
-    let mut character =
-        character_result.unwrap();
-    let character_body: (
-        &Transform,
-        &GlobalTransform,
-        &Collider,
-    ) = character_body_query.single();
-    let character_velocity =
-        character.2;
-    let character_hips_position =
-        character_body.1.translation();
-    let character_hips_down =
-        character_body.1.down();
-    let character_hips_height =
-        character_body.0.translation.y;
-
-    // TOOD consider changing logic to specially consider "current" stage collider
-
-    // from hips
-    if let Some((
-        stage_entity,
-        ray_intersection,
-    )) = rapier_context
-        .cast_ray_and_get_normal(
-            character_hips_position,
-            character_hips_down,
-            character_hips_height
-                + 0.16,
-            true,
+        let character_body: (
+            Entity,
+            &Transform,
+            &GlobalTransform,
+            &Collider,
+        ) = character_body_result.unwrap();
+
+        let rotation_from_global_up_to_character_up =
+            Quat::from_rotation_arc(
+                Vec3::Y,
+                *character.2.up(),
+            );
+        let global_horizontal_velocity = Quat::mul_vec3(
+            rotation_from_global_up_to_character_up,
+            Vec3::new(
+                character.4.global_horizontal_velocity.x,
+                0.0,
+                character.4.global_horizontal_velocity.y,
+            ),
+        );
+        let speed =
+            global_horizontal_velocity
+                .length();
+
+        if speed <= 0.0 {
+            continue;
+        }
+
+        let (_, body_rotation, body_translation) =
+            character_body
+                .2
+                .to_scale_rotation_translation();
+
+        let stage_query_filter =
             QueryFilter::new().groups(
                 CollisionGroups::new(
-                    Group::from_bits(
-                        0b0100,
-                    )
-                    .unwrap(),
-                    Group::from_bits(
-                        0b0010,
-                    )
-                    .unwrap(),
+                    Group::from_bits(0b0100)
+                        .unwrap(),
+                    Group::from_bits(0b0010)
+                        .unwrap(),
                 ),
-            ),
+            );
+
+        // step up, preserving speed
+        // ie, from the feet, as steps can be lower than the bottom of the body
+        let step_hit_result = rapier_context
+            .cast_ray_and_get_normal(
+                character.2.translation
+                    + character.2.up()
+                        * character
+                            .6
+                            .step_probe_height,
+                global_horizontal_velocity
+                    / speed,
+                speed * time.delta_seconds()
+                    + character
+                        .3
+                        .wall_contact_distance,
+                true,
+                stage_query_filter,
+            );
+
+        if let Some((_, step_hit)) =
+            step_hit_result
+        {
+            // steep enough to step onto, rather than walk up
+            if Vec3::angle_between(
+                step_hit.normal,
+                *character.2.up(),
+            ) >= character
+                .3
+                .minimum_wall_angle
+            {
+                if let Some(step_top) =
+                    find_step_top(
+                        &rapier_context,
+                        &character.2,
+                        body_translation,
+                        body_rotation,
+                        character_body.3,
+                        step_hit.point,
+                        character.6,
+                        character
+                            .3
+                            .minimum_wall_angle,
+                    )
+                {
+                    // up onto the step, then forward by velocity
+                    let character_up =
+                        *character.2.up();
+                    let step_height = Vec3::dot(
+                        step_top
+                            - character
+                                .2
+                                .translation,
+                        character_up,
+                    );
+                    move_character_body(
+                        &rapier_context,
+                        &mut character.2,
+                        character_body.0,
+                        character_body.1,
+                        character_body.3,
+                        character_up
+                            * step_height,
+                        character_up,
+                        character.10,
+                    );
+
+                    continue;
+                }
+            }
+        }
+
+        // max time of impact is in seconds, as the cast is by velocity
+        let wall_hit_result = rapier_context
+            .cast_shape(
+                body_translation,
+                body_rotation,
+                global_horizontal_velocity,
+                character_body.3,
+                time.delta_seconds()
+                    + character
+                        .3
+                        .wall_contact_distance
+                        / speed,
+                false,
+                stage_query_filter,
+            );
+
+        if wall_hit_result.is_none() {
+            continue;
+        }
+
+        let (wall_entity, wall_hit) =
+            wall_hit_result.unwrap();
+
+        if wall_hit.details.is_none() {
+            continue;
+        }
+
+        let wall_hit_details =
+            wall_hit.details.unwrap();
+        let wall_normal =
+            wall_hit_details.normal1;
+
+        // shallow enough to move onto
+        if Vec3::angle_between(
+            wall_normal,
+            *character.2.up(),
+        ) < character
+            .3
+            .minimum_wall_angle
+        {
+            continue;
+        }
+
+        // vault, preserving speed
+        if speed
+            >= character
+                .7
+                .minimum_vault_speed
+            && character
+                .5
+                .phase
+                .can_transition_to(
+                    CharacterPhase::Vault,
+                )
+        {
+            if let Some((
+                obstacle_height,
+                vault_landing_position,
+            )) = find_vault_landing(
+                &rapier_context,
+                &character.2,
+                body_translation,
+                character_body.3,
+                wall_hit_details.witness1,
+                character.7,
+                character.6,
+                character
+                    .3
+                    .minimum_wall_angle,
+            ) {
+                let vault_distance = Vec3::distance(
+                    character.2.translation,
+                    vault_landing_position,
+                );
+
+                commands
+                    .entity(character.0)
+                    .remove::<CharacterStageComponent>()
+                    .insert(CharacterVaultComponent {
+                        start_position: character
+                            .2
+                            .translation,
+                        end_position:
+                            vault_landing_position,
+                        arc_height: obstacle_height
+                            + character
+                                .7
+                                .vault_clearance_height,
+                        up: find_gravity_up(
+                            &character.2,
+                            character.9,
+                        ),
+                        vault_duration:
+                            vault_distance
+                                / speed,
+                        global_horizontal_velocity:
+                            global_horizontal_velocity
+                                .xz(),
+                    });
+
+                character.5.next_phase =
+                    Some(CharacterPhase::Vault);
+
+                continue;
+            }
+        }
+
+        // run along the wall, preserving speed
+        if speed
+            >= character
+                .8
+                .minimum_entry_speed
+            && is_wall_runnable(
+                wall_normal,
+                character.9.direction,
+                character.8,
+            )
+        {
+            stick_character_to_wall(
+                &mut commands,
+                character.0,
+                &mut character.2,
+                &mut character.4,
+                global_horizontal_velocity,
+                wall_entity,
+                wall_hit_details.witness1,
+                wall_normal,
+            );
+
+            character.5.next_phase =
+                Some(CharacterPhase::WallRun);
+
+            continue;
+        }
+
+        let rotation_from_character_up_to_global_up =
+            rotation_from_global_up_to_character_up
+                .inverse();
+        let local_wall_normal = Quat::mul_vec3(
+            rotation_from_character_up_to_global_up,
+            wall_normal,
         )
+        .xz()
+        .normalize_or_zero();
+
+        let impact_speed = -Vec2::dot(
+            character
+                .4
+                .global_horizontal_velocity,
+            local_wall_normal,
+        );
+
+        if impact_speed <= 0.0 {
+            continue;
+        }
+
+        if impact_speed
+            < character
+                .3
+                .minimum_bonk_speed
+        {
+            // slide along the wall
+            character
+                .4
+                .global_horizontal_velocity +=
+                local_wall_normal
+                    * impact_speed;
+
+            continue;
+        }
+
+        character
+            .4
+            .global_horizontal_velocity =
+            local_wall_normal
+                * impact_speed
+                * character
+                    .3
+                    .bonk_rebound_speed_scale;
+        character.5.next_phase =
+            Some(CharacterPhase::Bonk);
+
+        bonk_events.send(
+            CharacterBonkEvent {
+                character_entity: character
+                    .0,
+                wall_entity,
+                impact_speed,
+                wall_normal,
+            },
+        );
+    }
+}
+
+/// system to update movement velocity of a character which is in the air
+pub fn update_character_movement_velocity_while_in_air_phase_system(
+    time: Res<Time>,
+    mut character_query: Query<
+        (
+            &CharacterFallPhaseMovementParametersComponent,
+            &mut CharacterMovementVariablesComponent,
+            &CharacterGravityVariablesComponent,
+        ),
+        (
+            With<CharacterTagComponent>,
+            Without<CharacterStageComponent>,
+            Without<
+                CharacterLedgeGetupComponent,
+            >,
+            Without<CharacterVaultComponent>,
+        ),
+    >,
+) {
+    for mut character in
+        character_query.iter_mut()
     {
-        // println!(
-        //     "moving on stage. {} {}",
-        //     character_hips_position, character_hips_down
-        // );
+        let gravity_up = -character.2.direction;
 
-        // snap to ground
-        let rotation: Quat =
+        // in the air, velocity is global
+        let global_velocity = Vec3::new(
+            character
+                .1
+                .global_horizontal_velocity
+                .x,
+            character
+                .1
+                .local_vertical_velocity,
+            character
+                .1
+                .global_horizontal_velocity
+                .y,
+        );
+
+        // speed against gravity
+        let up_speed = Vec3::dot(
+            global_velocity,
+            gravity_up,
+        );
+        let next_up_speed = f32::clamp(
+            up_speed
+                - character
+                    .0
+                    .down_acceleration
+                    * time.delta_seconds(),
+            -character
+                .0
+                .maximum_down_speed,
+            character
+                .0
+                .maximum_up_speed,
+        );
+        let next_global_velocity =
+            global_velocity
+                + gravity_up
+                    * (next_up_speed
+                        - up_speed);
+
+        character
+            .1
+            .global_horizontal_velocity =
+            next_global_velocity.xz();
+        character
+            .1
+            .local_vertical_velocity =
+            next_global_velocity.y;
+    }
+}
+
+/// system to update physics body velocity for a character using movement velocity
+pub fn update_character_body_velocity_while_on_stage_using_movement_velocity_system(
+    mut character_query: Query<
+        (
+            &Transform,
+            &CharacterPlayerInputComponent,
+            &CharacterTransformationFromPlayerToCameraVariablesComponent,
+            &CharacterMovementVariablesComponent,
+            &mut Velocity,
+        ),
+        (With<CharacterTagComponent>, With<CharacterStageComponent>),
+    >,
+) {
+    for mut character in
+        character_query.iter_mut()
+    {
+        let rotation_from_global_up_to_character_up =
             Quat::from_rotation_arc(
-                *character.3.up(),
-                ray_intersection.normal,
+                Vec3::Y,
+                *character.0.up(),
             );
-        character.3.rotation *=
-            rotation;
-        character.3.translation =
-            ray_intersection.point;
-
-        commands
-            .entity(character.0)
-            .insert(
-            CharacterStageComponent {
-                stage_entity:
-                    stage_entity,
-            },
+        let next_body_global_horizontal_velocity = Quat::mul_vec3(
+            rotation_from_global_up_to_character_up,
+            Vec3::new(
+                character.3.global_horizontal_velocity.x,
+                0.0,
+                character.3.global_horizontal_velocity.y,
+            ),
         );
+        let next_body_global_vertical_velocity =
+            character.0.up()
+                * character
+                    .3
+                    .local_vertical_velocity;
+        character.4.linvel = next_body_global_horizontal_velocity + next_body_global_vertical_velocity;
+    }
+}
 
-        return;
+/// system to update physics body velocity for a character using movement velocity
+pub fn update_character_body_velocity_while_in_air_using_movement_velocity_system(
+    mut character_query: Query<
+        (
+            &Transform,
+            &CharacterPlayerInputComponent,
+            &CharacterTransformationFromPlayerToCameraVariablesComponent,
+            &CharacterMovementVariablesComponent,
+            &mut Velocity,
+        ),
+        (
+            With<CharacterTagComponent>,
+            Without<CharacterStageComponent>,
+        ),
+    >,
+) {
+    for mut character in
+        character_query.iter_mut()
+    {
+        character.4.linvel = Vec3::new(
+            character
+                .3
+                .global_horizontal_velocity
+                .x,
+            character
+                .3
+                .local_vertical_velocity,
+            character
+                .3
+                .global_horizontal_velocity
+                .y,
+        )
     }
+}
 
-    // moving upwards, did not collide from hips
-    // try feet
-    if character_velocity.linvel.y > 0.0
+/// snap a character onto the ground found below its body, by moving its body through the world.
+/// ie, turn its up towards the ground normal, then move it along its up onto the ground.
+fn snap_character_to_ground(
+    rapier_context: &RapierContext,
+    character_transform: &mut Transform,
+    body_entity: Entity,
+    body_transform: &Transform,
+    body_collider: &Collider,
+    ground: &MoveShapeHit,
+    move_shape_parameters: &CharacterMoveShapeParametersComponent,
+) {
+    character_transform.rotation =
+        Quat::from_rotation_arc(
+            *character_transform.up(),
+            ground.normal,
+        ) * character_transform.rotation;
+
+    let character_up =
+        *character_transform.up();
+    move_character_body(
+        rapier_context,
+        character_transform,
+        body_entity,
+        body_transform,
+        body_collider,
+        character_up
+            * Vec3::dot(
+                ground.point
+                    - character_transform
+                        .translation,
+                character_up,
+            ),
+        character_up,
+        move_shape_parameters,
+    );
+}
+
+/// maximum number of triangles walked across, when finding the crossed edge of the stage.
+const MAXIMUM_CROSSED_STAGE_TRIANGLES: usize = 16;
+
+/// distance past a crossed edge to probe for the stage, in m.
+const CROSSED_STAGE_EDGE_PROBE_OFFSET: f32 = 0.01;
+
+/// find the boundary edge of the stage crossed by a character moving from previous feet position to feet position.
+/// ie, walks the triangles along the path, starting from the triangle below the previous feet position.
+/// nb, an edge is only a boundary if a probe just past it misses the stage. otherwise, the walk continues into the next triangle.
+/// only triangle mesh stages have edges.
+fn find_crossed_stage_edge(
+    stage_collider: &Collider,
+    stage_global_transform: &GlobalTransform,
+    previous_feet_position: Vec3,
+    feet_position: Vec3,
+    character_up: Vec3,
+    probe_distance: f32,
+) -> Option<(Vec3, Vec3)> {
+    let stage_trimesh =
+        stage_collider.as_trimesh()?;
+    let (_, stage_rotation, stage_translation) =
+        stage_global_transform
+            .to_scale_rotation_translation();
+
+    let path_direction = (feet_position
+        - previous_feet_position)
+        .normalize_or_zero();
+    let mut path_start =
+        previous_feet_position;
+
+    for _ in 0..MAXIMUM_CROSSED_STAGE_TRIANGLES
     {
-        // TODO calculate exact length of raycast using trigommetry and maximum incline
+        let stage_intersection = stage_collider
+            .cast_ray_and_get_normal(
+                stage_translation,
+                stage_rotation,
+                path_start
+                    + character_up
+                        * probe_distance,
+                -character_up,
+                probe_distance * 2.0,
+                true,
+            )?;
+
+        let FeatureId::Face(face_index) =
+            stage_intersection.feature
+        else {
+            return None;
+        };
+
+        // back faces are indexed after front faces
+        let triangle_index = face_index
+            % stage_trimesh.indices().len()
+                as u32;
+        let (
+            local_vertex_a,
+            local_vertex_b,
+            local_vertex_c,
+        ) = stage_trimesh
+            .triangle(triangle_index);
+        let vertices = [
+            local_vertex_a,
+            local_vertex_b,
+            local_vertex_c,
+        ]
+        .map(|local_vertex| {
+            stage_translation
+                + stage_rotation
+                    * local_vertex
+        });
+
+        let triangle_normal = Vec3::cross(
+            vertices[1] - vertices[0],
+            vertices[2] - vertices[0],
+        )
+        .normalize_or_zero();
+
+        if triangle_normal == Vec3::ZERO {
+            return None;
+        }
 
-        // from feet
-        let character_feet_position =
-            character_hips_position + character_hips_down * character_hips_height;
-        let character_feet_snap_distance =
-            0.32;
+        // first edge crossed, from inside to outside
+        let mut crossed_edge: Option<(
+            f32,
+            Vec3,
+            Vec3,
+        )> = None;
+        for edge_index in 0..3 {
+            let edge_start =
+                vertices[edge_index];
+            let edge_end = vertices
+                [(edge_index + 1) % 3];
+            let opposite_vertex = vertices
+                [(edge_index + 2) % 3];
+
+            let mut edge_outward = Vec3::cross(
+                triangle_normal,
+                edge_end - edge_start,
+            )
+            .normalize_or_zero();
+            if Vec3::dot(
+                edge_outward,
+                opposite_vertex - edge_start,
+            ) > 0.0
+            {
+                edge_outward = -edge_outward;
+            }
+
+            let previous_distance = Vec3::dot(
+                path_start - edge_start,
+                edge_outward,
+            );
+            let distance = Vec3::dot(
+                feet_position - edge_start,
+                edge_outward,
+            );
 
-        //println!("moving downwards on stage. {}", character_feet_position);
+            if previous_distance > 0.0
+                || distance <= 0.0
+            {
+                continue;
+            }
+
+            let crossing_fraction =
+                previous_distance
+                    / (previous_distance
+                        - distance);
+
+            if crossed_edge.is_some_and(
+                |(fraction, _, _)| {
+                    fraction
+                        <= crossing_fraction
+                },
+            ) {
+                continue;
+            }
+
+            crossed_edge = Some((
+                crossing_fraction,
+                edge_start,
+                edge_outward,
+            ));
+        }
 
-        if let Some((stage_entity, ray_intersection)) = rapier_context.cast_ray_and_get_normal(
-            character_feet_position,
-            Vec3::NEG_Y,
-            character_feet_snap_distance,
-            true,
-            QueryFilter::new().groups(CollisionGroups::new(
-                Group::from_bits(0b0100).unwrap(),
-                Group::from_bits(0b0010).unwrap(),
-            )),
-        ) {
-            // snap to ground
-            let rotation = Quat::from_rotation_arc(*character.3.up(), ray_intersection.normal);
-            character.3.rotation *= rotation;
-            character.3.translation = ray_intersection.point;
+        // the path ends inside of this triangle
+        let (
+            crossing_fraction,
+            edge_start,
+            edge_outward,
+        ) = crossed_edge?;
+
+        let crossing_point = Vec3::lerp(
+            path_start,
+            feet_position,
+            crossing_fraction,
+        );
+        let edge_point = crossing_point
+            - triangle_normal
+                * Vec3::dot(
+                    crossing_point
+                        - edge_start,
+                    triangle_normal,
+                );
+
+        // interior edge, ie, the stage continues past it
+        if stage_collider
+            .cast_ray(
+                stage_translation,
+                stage_rotation,
+                edge_point
+                    + edge_outward
+                        * CROSSED_STAGE_EDGE_PROBE_OFFSET
+                    + character_up
+                        * probe_distance,
+                -character_up,
+                probe_distance * 2.0,
+                true,
+            )
+            .is_some()
+        {
+            path_start = crossing_point
+                + path_direction
+                    * CROSSED_STAGE_EDGE_PROBE_OFFSET;
+            continue;
+        }
+
+        return Some((
+            edge_point,
+            edge_outward,
+        ));
+    }
+
+    return None;
+}
+
+/// system to update a character body and stage status while on stage
+/// ie, stay on the ground found by the last move of the body, otherwise catch or trip on the edge of the stage, or leave it.
+pub fn update_character_body_while_on_stage_system(
+    time: Res<Time>,
+    rapier_context: Res<RapierContext>,
+    mut commands: Commands,
+    mut character_query: Query<
+        (
+            Entity,
+            &Children,
+            &mut Velocity,
+            &mut Transform,
+            &mut CharacterPhaseVariablesComponent,
+            &CharacterMoveShapeParametersComponent,
+            &CharacterStageComponent,
+            &CharacterLedgeCatchParametersComponent,
+            &mut CharacterMovementVariablesComponent,
+            &CharacterLedgeTripParametersComponent,
+            &CharacterMoveShapeVariablesComponent,
+        ),
+        With<CharacterTagComponent>,
+    >,
+    character_body_query: Query<
+        (Entity, &Transform, &Collider),
+        (
+            With<CharacterBodyTagComponent>,
+            Without<CharacterTagComponent>,
+        ),
+    >,
+    stage_query: Query<
+        (&GlobalTransform, &Collider),
+        (
+            Without<CharacterTagComponent>,
+            Without<
+                CharacterBodyTagComponent,
+            >,
+        ),
+    >,
+) {
+    for mut character in
+        character_query.iter_mut()
+    {
+        let character_body_result = character
+            .1
+            .iter()
+            .find_map(|child| {
+                character_body_query
+                    .get(*child)
+                    .ok()
+            });
+
+        if character_body_result.is_none() {
+            continue;
+        }
+
+        let character_body: (
+            Entity,
+            &Transform,
+            &Collider,
+        ) = character_body_result.unwrap();
+        let character_velocity =
+            character.2.linvel;
+        let character_hips_height =
+            character_body.1.translation.y;
+
+        // snap to ground
+        if let Some(ground) =
+            character.10.result.ground
+        {
+            snap_character_to_ground(
+                &rapier_context,
+                &mut character.3,
+                character_body.0,
+                character_body.1,
+                character_body.2,
+                &ground,
+                character.5,
+            );
 
             commands
                 .entity(character.0)
-                .insert(CharacterStageComponent {
-                    stage_entity: stage_entity,
-                });
+                .insert(
+                CharacterStageComponent {
+                    stage_entity: ground
+                        .entity,
+                },
+            );
 
-            return;
+            continue;
         }
-    }
 
-    // become airborne
-    // TODO instead, orient to inverse of "gravity"
-    character.3.rotation =
-        Quat::IDENTITY;
-    commands
-        .entity(character.0)
-        .remove::<CharacterStageComponent>();
+        // catch the edge of the stage, instead of leaving it
+        let do_catch_ledge = character
+            .4
+            .phase
+            != CharacterPhase::WallRun
+            && match character.7.mode {
+                CharacterLedgeCatchMode::AlwaysCatch => true,
+                CharacterLedgeCatchMode::CatchWhileWalking => {
+                    character
+                        .8
+                        .global_horizontal_velocity
+                        .length()
+                        <= character
+                            .7
+                            .maximum_walking_speed
+                }
+                CharacterLedgeCatchMode::Never => false,
+            };
+
+        // teeter on the edge of the stage, while slow enough
+        // ie, tripping stops at the edge, so characters which never catch the edge never trip on it either
+        let do_trip_ledge = character
+            .7
+            .mode
+            != CharacterLedgeCatchMode::Never
+            && (character.4.phase
+                == CharacterPhase::GroundLedgeTrip
+                || (matches!(
+                    character.4.phase,
+                    CharacterPhase::Grounded
+                        | CharacterPhase::Landing
+                ) && character
+                    .4
+                    .next_phase
+                    .is_none()
+                    && character
+                        .8
+                        .global_horizontal_velocity
+                        .length()
+                        <= character
+                            .9
+                            .maximum_trip_speed));
+
+        if do_catch_ledge || do_trip_ledge {
+            let stage_result = stage_query
+                .get(character.6.stage_entity);
+
+            if let Ok(stage) = stage_result {
+                let character_up =
+                    *character.3.up();
+                let previous_feet_position =
+                    character.3.translation
+                        - character_velocity
+                            * time.delta_seconds();
+
+                if let Some((
+                    edge_point,
+                    edge_outward,
+                )) = find_crossed_stage_edge(
+                    stage.1,
+                    stage.0,
+                    previous_feet_position,
+                    character.3.translation,
+                    character_up,
+                    character_hips_height,
+                ) {
+                    let ledge_catch_position =
+                        edge_point
+                            - edge_outward
+                                * character
+                                    .7
+                                    .ledge_catch_margin;
+                    let ledge_catch_motion =
+                        ledge_catch_position
+                            - character
+                                .3
+                                .translation;
+                    move_character_body(
+                        &rapier_context,
+                        &mut character.3,
+                        character_body.0,
+                        character_body.1,
+                        character_body.2,
+                        ledge_catch_motion,
+                        character_up,
+                        character.5,
+                    );
+
+                    // cancel velocity off the edge
+                    let outward_speed = f32::max(
+                        Vec3::dot(
+                            character_velocity,
+                            edge_outward,
+                        ),
+                        0.0,
+                    );
+                    character.2.linvel -=
+                        edge_outward
+                            * outward_speed;
+
+                    let local_edge_outward = Quat::mul_vec3(
+                        Quat::from_rotation_arc(
+                            character_up,
+                            Vec3::Y,
+                        ),
+                        edge_outward,
+                    )
+                    .xz()
+                    .normalize_or_zero();
+                    let local_outward_speed = f32::max(
+                        Vec2::dot(
+                            character
+                                .8
+                                .global_horizontal_velocity,
+                            local_edge_outward,
+                        ),
+                        0.0,
+                    );
+                    character
+                        .8
+                        .global_horizontal_velocity -=
+                        local_edge_outward
+                            * local_outward_speed;
+
+                    if do_trip_ledge {
+                        commands
+                            .entity(character.0)
+                            .insert(CharacterLedgeTripComponent {
+                                edge_point,
+                                edge_normal: edge_outward,
+                            });
+
+                        if character.4.phase
+                            != CharacterPhase::GroundLedgeTrip
+                        {
+                            character.4.next_phase = Some(
+                                CharacterPhase::GroundLedgeTrip,
+                            );
+                        }
+                    }
+
+                    continue;
+                }
+            }
+        }
+
+        // become airborne
+        // ie, keep orientation, which turns towards the inverse of gravity while in the air
+        let global_velocity =
+            find_global_velocity_while_on_stage(
+                *character.3.up(),
+                &character.8,
+            );
+        character
+            .8
+            .global_horizontal_velocity =
+            global_velocity.xz();
+        character
+            .8
+            .local_vertical_velocity =
+            global_velocity.y;
+        commands
+            .entity(character.0)
+            .remove::<CharacterStageComponent>();
 
-    println!("leave");
+        character.4.next_phase =
+            Some(CharacterPhase::Falling);
+    }
 }
 
 /// system to update the stage status of a character while that charcter is on stage
@@ -505,56 +2048,76 @@ pub fn update_character_body_try_jump_while_on_stage_system(
             Entity,
             &mut Transform,
             &mut CharacterMovementVariablesComponent,
+            &mut CharacterPhaseVariablesComponent,
+            &CharacterWallRunParametersComponent,
         ),
         (With<CharacterTagComponent>, With<CharacterStageComponent>),
     >,
 ) {
-    let character_result =
-        character_query
-            .get_single_mut();
+    for mut character in
+        character_query.iter_mut()
+    {
+        let vertical_velocity = character
+            .2
+            .local_vertical_velocity;
 
-    if character_result.is_err() {
-        return;
-    }
+        if vertical_velocity <= 0.0 {
+            continue;
+        }
 
-    let mut character =
-        character_result.unwrap();
+        if character.3.phase
+            == CharacterPhase::WallRun
+        {
+            // jump off the wall
+            // ie, the wall is not horizontal, so velocity along the wall is global
+            let mut jump_velocity =
+                find_global_velocity_while_on_stage(
+                    *character.1.up(),
+                    &character.2,
+                );
+            jump_velocity.y += character
+                .4
+                .wall_jump_up_speed;
 
-    let vertical_velocity = character
-        .2
-        .local_vertical_velocity;
+            character
+                .2
+                .global_horizontal_velocity =
+                jump_velocity.xz();
+            character
+                .2
+                .local_vertical_velocity =
+                jump_velocity.y;
+        } else {
+            let jump_velocity =
+                character.1.up()
+                    * vertical_velocity;
 
-    if vertical_velocity <= 0.0 {
-        return;
-    }
+            character
+                .2
+                .global_horizontal_velocity
+                .x += jump_velocity.x;
+            character
+                .2
+                .global_horizontal_velocity
+                .y += jump_velocity.z;
+            character
+                .2
+                .local_vertical_velocity =
+                jump_velocity.y;
+        }
 
-    let jump_velocity =
-        character.1.up()
-            * vertical_velocity;
-
-    character
-        .2
-        .global_horizontal_velocity
-        .x += jump_velocity.x;
-    character
-        .2
-        .global_horizontal_velocity
-        .y += jump_velocity.z;
-    character
-        .2
-        .local_vertical_velocity =
-        jump_velocity.y;
-    character.1.rotation =
-        Quat::IDENTITY;
-
-    println!("jump");
+        commands
+            .entity(character.0)
+            .remove::<CharacterStageComponent>()
+            .remove::<CharacterLedgeTripComponent>();
 
-    commands
-        .entity(character.0)
-        .remove::<CharacterStageComponent>();
+        character.3.next_phase =
+            Some(CharacterPhase::Jumping);
+    }
 }
 
 /// system to update the stage status of a character while that charcter is not on stage
+/// ie, land on the ground found by the last move of the body, while moving along gravity.
 pub fn update_character_body_try_land_while_in_air_system(
     rapier_context: Res<RapierContext>,
     mut commands: Commands,
@@ -565,107 +2128,1160 @@ pub fn update_character_body_try_land_while_in_air_system(
             &mut Velocity,
             &mut CharacterMovementVariablesComponent,
             &mut Transform,
+            &mut CharacterPhaseVariablesComponent,
+            &CharacterMoveShapeParametersComponent,
+            &CharacterGravityVariablesComponent,
+            &CharacterMoveShapeVariablesComponent,
         ),
         (
             With<CharacterTagComponent>,
             Without<CharacterStageComponent>,
+            Without<
+                CharacterLedgeGetupComponent,
+            >,
+            Without<CharacterVaultComponent>,
         ),
     >,
     character_body_query: Query<
-        (&Transform, &GlobalTransform, &Collider),
+        (Entity, &Transform, &Collider),
         (
             With<CharacterBodyTagComponent>,
             Without<CharacterTagComponent>,
         ),
     >,
 ) {
-    let character_result =
-        character_query
-            .get_single_mut();
-
-    if character_result.is_err() {
-        return;
-    }
+    for mut character in
+        character_query.iter_mut()
+    {
+        // moving against gravity
+        if Vec3::dot(
+            character.2.linvel,
+            character.7.direction,
+        ) <= 0.0
+        {
+            continue;
+        }
 
-    let mut character =
-        character_result.unwrap();
-
-    let character_body: (
-        &Transform,
-        &GlobalTransform,
-        &Collider,
-    ) = character_body_query.single();
-    let character_hips_position =
-        character_body.1.translation();
-    let character_hips_down =
-        character_body.1.down();
-    let character_hips_height =
-        character_body.0.translation.y;
-
-    // TOOD consider changing logic to specially consider "current" stage collider
-
-    if character.2.linvel.y >= 0.0 {
-        return;
-    }
+        if character.8.result.ground.is_none()
+        {
+            continue;
+        }
 
-    //println!("moving down in air");
+        let ground = character
+            .8
+            .result
+            .ground
+            .unwrap();
+
+        let character_body_result = character
+            .1
+            .iter()
+            .find_map(|child| {
+                character_body_query
+                    .get(*child)
+                    .ok()
+            });
+
+        if character_body_result.is_none() {
+            continue;
+        }
 
-    // from hips
-    if let Some((
-        stage_entity,
-        ray_intersection,
-    )) = rapier_context
-        .cast_ray_and_get_normal(
-            character_hips_position,
-            character_hips_down,
-            character_hips_height
-                + 0.16,
-            true,
-            QueryFilter::new().groups(
+        let character_body: (
+            Entity,
+            &Transform,
+            &Collider,
+        ) = character_body_result.unwrap();
+
+        snap_character_to_ground(
+            &rapier_context,
+            &mut character.4,
+            character_body.0,
+            character_body.1,
+            character_body.2,
+            &ground,
+            character.6,
+        );
+        character.2.linvel =
+            Vec3::reject_from_normalized(
+                character.2.linvel,
+                character.7.direction,
+            );
+        character
+            .3
+            .local_vertical_velocity = 0.0;
+
+        commands
+            .entity(character.0)
+            .insert(CharacterStageComponent {
+                stage_entity: ground.entity,
+            });
+
+        character.5.next_phase =
+            Some(CharacterPhase::Landing);
+    }
+}
+
+/// system to catch a ledge in front of a character which is in the air.
+/// ie, the body touches the edge of a ledge, and the top of the ledge is within reach of the hips.
+pub fn update_character_body_try_ledge_getup_while_in_air_system(
+    rapier_context: Res<RapierContext>,
+    mut commands: Commands,
+    mut character_query: Query<
+        (
+            Entity,
+            &Children,
+            &Transform,
+            &CharacterMovementVariablesComponent,
+            &CharacterLedgeGetupParametersComponent,
+            &CharacterBonkParametersComponent,
+            &mut CharacterPhaseVariablesComponent,
+            &CharacterGravityVariablesComponent,
+        ),
+        (
+            With<CharacterTagComponent>,
+            Without<CharacterStageComponent>,
+            Without<
+                CharacterLedgeGetupComponent,
+            >,
+            Without<CharacterVaultComponent>,
+        ),
+    >,
+    character_body_query: Query<
+        (&Transform, &GlobalTransform, &Collider),
+        (
+            With<CharacterBodyTagComponent>,
+            Without<CharacterTagComponent>,
+        ),
+    >,
+) {
+    for mut character in
+        character_query.iter_mut()
+    {
+        if !matches!(
+            character.6.phase,
+            CharacterPhase::Jumping
+                | CharacterPhase::Falling
+        ) || character
+            .6
+            .next_phase
+            .is_some()
+        {
+            continue;
+        }
+
+        let character_body_result = character
+            .1
+            .iter()
+            .find_map(|child| {
+                character_body_query
+                    .get(*child)
+                    .ok()
+            });
+
+        if character_body_result.is_none() {
+            continue;
+        }
+
+        let character_body: (
+            &Transform,
+            &GlobalTransform,
+            &Collider,
+        ) = character_body_result.unwrap();
+
+        let gravity_up = find_gravity_up(
+            character.2,
+            character.7,
+        );
+
+        // in the air, velocity is global. forward is across gravity
+        let global_velocity = Vec3::new(
+            character
+                .3
+                .global_horizontal_velocity
+                .x,
+            character
+                .3
+                .local_vertical_velocity,
+            character
+                .3
+                .global_horizontal_velocity
+                .y,
+        );
+        let forward = (global_velocity
+            - gravity_up
+                * Vec3::dot(
+                    global_velocity,
+                    gravity_up,
+                ))
+        .normalize_or_zero();
+
+        if forward == Vec3::ZERO {
+            continue;
+        }
+
+        let (_, body_rotation, body_translation) =
+            character_body
+                .1
+                .to_scale_rotation_translation();
+        let character_hips_height =
+            character_body.0.translation.y;
+
+        let stage_query_filter =
+            QueryFilter::new().groups(
                 CollisionGroups::new(
-                    Group::from_bits(
-                        0b0100,
-                    )
-                    .unwrap(),
-                    Group::from_bits(
-                        0b0010,
-                    )
-                    .unwrap(),
+                    Group::from_bits(0b0100)
+                        .unwrap(),
+                    Group::from_bits(0b0010)
+                        .unwrap(),
+                ),
+            );
+
+        // from body, forward into the edge of the ledge
+        let ledge_hit_result = rapier_context
+            .cast_shape(
+                body_translation,
+                body_rotation,
+                forward,
+                character_body.2,
+                character
+                    .4
+                    .ledge_contact_distance,
+                false,
+                stage_query_filter,
+            );
+
+        if ledge_hit_result.is_none() {
+            continue;
+        }
+
+        let (ledge_entity, ledge_hit) =
+            ledge_hit_result.unwrap();
+
+        if ledge_hit.details.is_none() {
+            continue;
+        }
+
+        let ledge_hit_details =
+            ledge_hit.details.unwrap();
+
+        if Vec3::angle_between(
+            ledge_hit_details.normal1,
+            gravity_up,
+        ) < character
+            .5
+            .minimum_wall_angle
+        {
+            continue;
+        }
+
+        // from above the reach, down onto the top of the ledge
+        let ledge_probe_position = ledge_hit_details
+            .witness1
+            + gravity_up
+                * (Vec3::dot(
+                    body_translation
+                        - ledge_hit_details
+                            .witness1,
+                    gravity_up,
+                ) + character
+                    .4
+                    .maximum_reach_height_above_hips)
+            + forward
+                * character
+                    .4
+                    .ledge_forward_probe_distance;
+
+        let ledge_top_result = rapier_context
+            .cast_ray_and_get_normal(
+                ledge_probe_position,
+                -gravity_up,
+                character
+                    .4
+                    .maximum_reach_height_above_hips
+                    + character
+                        .4
+                        .maximum_reach_depth_below_hips,
+                true,
+                stage_query_filter,
+            );
+
+        if ledge_top_result.is_none() {
+            continue;
+        }
+
+        let (_, ledge_top_hit) =
+            ledge_top_result.unwrap();
+
+        // inside geometry taller than the reach
+        if ledge_top_hit.toi <= 0.0 {
+            continue;
+        }
+
+        if Vec3::angle_between(
+            ledge_top_hit.normal,
+            gravity_up,
+        ) >= character
+            .5
+            .minimum_wall_angle
+        {
+            continue;
+        }
+
+        // room for the body on top of the ledge
+        if rapier_context
+            .intersection_with_shape(
+                ledge_top_hit.point
+                    + gravity_up
+                        * character_hips_height,
+                Quat::from_rotation_arc(
+                    Vec3::Y,
+                    gravity_up,
                 ),
+                character_body.2,
+                stage_query_filter,
+            )
+            .is_some()
+        {
+            continue;
+        }
+
+        commands
+            .entity(character.0)
+            .insert(
+                CharacterLedgeGetupComponent {
+                    ledge_entity,
+                    hang_position: character
+                        .2
+                        .translation,
+                    ledge_top_position:
+                        ledge_top_hit.point,
+                    up: gravity_up,
+                },
+            );
+
+        character.6.next_phase =
+            Some(CharacterPhase::LedgeGetup);
+    }
+}
+
+/// system to pull a character up onto the ledge it caught, then place it on top.
+pub fn update_character_body_while_ledge_getup_system(
+    rapier_context: Res<RapierContext>,
+    mut commands: Commands,
+    mut character_query: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut Velocity,
+            &mut CharacterMovementVariablesComponent,
+            &CharacterLedgeGetupParametersComponent,
+            &mut CharacterPhaseVariablesComponent,
+            &CharacterLedgeGetupComponent,
+            &Children,
+            &CharacterMoveShapeParametersComponent,
+        ),
+        With<CharacterTagComponent>,
+    >,
+    character_body_query: Query<
+        (Entity, &Transform, &Collider),
+        (
+            With<CharacterBodyTagComponent>,
+            Without<CharacterTagComponent>,
+        ),
+    >,
+) {
+    for mut character in
+        character_query.iter_mut()
+    {
+        // locked to the ledge
+        character.2.linvel = Vec3::ZERO;
+        character
+            .3
+            .global_horizontal_velocity =
+            Vec2::ZERO;
+        character
+            .3
+            .local_vertical_velocity = 0.0;
+
+        if character.5.phase
+            != CharacterPhase::LedgeGetup
+        {
+            continue;
+        }
+
+        let pull_up_progress = f32::clamp(
+            character.5.phase_time
+                / character
+                    .4
+                    .pull_up_duration,
+            0.0,
+            1.0,
+        );
+
+        // up the face of the ledge, then forward onto the top
+        let pull_up_top_position = character
+            .6
+            .hang_position
+            + character.6.up
+                * Vec3::dot(
+                    character
+                        .6
+                        .ledge_top_position
+                        - character
+                            .6
+                            .hang_position,
+                    character.6.up,
+                );
+        let pull_up_position =
+            if pull_up_progress < 0.5 {
+                Vec3::lerp(
+                    character.6.hang_position,
+                    pull_up_top_position,
+                    pull_up_progress * 2.0,
+                )
+            } else {
+                Vec3::lerp(
+                    pull_up_top_position,
+                    character
+                        .6
+                        .ledge_top_position,
+                    pull_up_progress * 2.0
+                        - 1.0,
+                )
+            };
+
+        let character_body_result = character
+            .7
+            .iter()
+            .find_map(|child| {
+                character_body_query
+                    .get(*child)
+                    .ok()
+            });
+
+        if character_body_result.is_none() {
+            continue;
+        }
+
+        let character_body: (
+            Entity,
+            &Transform,
+            &Collider,
+        ) = character_body_result.unwrap();
+
+        let pull_up_motion =
+            pull_up_position
+                - character.1.translation;
+        move_character_body(
+            &rapier_context,
+            &mut character.1,
+            character_body.0,
+            character_body.1,
+            character_body.2,
+            pull_up_motion,
+            character.6.up,
+            character.8,
+        );
+
+        if pull_up_progress < 1.0 {
+            continue;
+        }
+
+        commands
+            .entity(character.0)
+            .remove::<CharacterLedgeGetupComponent>()
+            .insert(CharacterStageComponent {
+                stage_entity: character
+                    .6
+                    .ledge_entity,
+            });
+
+        character.5.next_phase =
+            Some(CharacterPhase::Landing);
+    }
+}
+
+/// system to turn a character which is in the air so its up is towards the inverse of gravity.
+/// ie, leaving loops and curved ramps does not snap the character upright.
+pub fn update_character_body_rotation_while_in_air_system(
+    time: Res<Time>,
+    mut character_query: Query<
+        (
+            &mut Transform,
+            &CharacterAirOrientationParametersComponent,
+            &CharacterGravityVariablesComponent,
+        ),
+        (
+            With<CharacterTagComponent>,
+            Without<CharacterStageComponent>,
+            Without<
+                CharacterLedgeGetupComponent,
+            >,
+            Without<CharacterVaultComponent>,
+        ),
+    >,
+) {
+    for mut character in
+        character_query.iter_mut()
+    {
+        let target_up =
+            -character.2.direction;
+
+        if target_up == Vec3::ZERO {
+            continue;
+        }
+
+        let rotation_to_target_up =
+            Quat::from_rotation_arc(
+                *character.0.up(),
+                target_up,
+            );
+        let angle_to_target_up =
+            Quat::angle_between(
+                rotation_to_target_up,
+                Quat::IDENTITY,
+            );
+
+        if angle_to_target_up <= 0.0 {
+            continue;
+        }
+
+        let maximum_angle = character
+            .1
+            .air_rotation_speed
+            * time.delta_seconds();
+
+        character.0.rotation = Quat::slerp(
+            Quat::IDENTITY,
+            rotation_to_target_up,
+            f32::min(
+                maximum_angle
+                    / angle_to_target_up,
+                1.0,
             ),
-        )
+        ) * character.0.rotation;
+    }
+}
+
+/// system to stick a character which is in the air to a near-vertical wall in front of it, when fast enough.
+pub fn update_character_body_try_wall_run_while_in_air_system(
+    rapier_context: Res<RapierContext>,
+    mut commands: Commands,
+    mut character_query: Query<
+        (
+            Entity,
+            &Children,
+            &mut Transform,
+            &mut CharacterMovementVariablesComponent,
+            &CharacterWallRunParametersComponent,
+            &CharacterBonkParametersComponent,
+            &mut CharacterPhaseVariablesComponent,
+            &CharacterGravityVariablesComponent,
+        ),
+        (
+            With<CharacterTagComponent>,
+            Without<CharacterStageComponent>,
+            Without<
+                CharacterLedgeGetupComponent,
+            >,
+            Without<CharacterVaultComponent>,
+        ),
+    >,
+    character_body_query: Query<
+        (&GlobalTransform, &Collider),
+        (
+            With<CharacterBodyTagComponent>,
+            Without<CharacterTagComponent>,
+        ),
+    >,
+) {
+    for mut character in
+        character_query.iter_mut()
     {
-        // snap to ground
-        // TODO set rotation directly
-        let rotation: Quat =
+        if !matches!(
+            character.6.phase,
+            CharacterPhase::Jumping
+                | CharacterPhase::Falling
+        ) || character
+            .6
+            .next_phase
+            .is_some()
+        {
+            continue;
+        }
+
+        let character_body_result = character
+            .1
+            .iter()
+            .find_map(|child| {
+                character_body_query
+                    .get(*child)
+                    .ok()
+            });
+
+        if character_body_result.is_none() {
+            continue;
+        }
+
+        let character_body: (
+            &GlobalTransform,
+            &Collider,
+        ) = character_body_result.unwrap();
+
+        // in the air, horizontal velocity is global
+        let global_velocity = Vec3::new(
+            character
+                .3
+                .global_horizontal_velocity
+                .x,
+            character
+                .3
+                .local_vertical_velocity,
+            character
+                .3
+                .global_horizontal_velocity
+                .y,
+        );
+        let horizontal_speed = character
+            .3
+            .global_horizontal_velocity
+            .length();
+
+        if horizontal_speed
+            < character
+                .4
+                .minimum_entry_speed
+        {
+            continue;
+        }
+
+        let forward = Vec3::new(
+            character
+                .3
+                .global_horizontal_velocity
+                .x,
+            0.0,
+            character
+                .3
+                .global_horizontal_velocity
+                .y,
+        ) / horizontal_speed;
+
+        let (_, body_rotation, body_translation) =
+            character_body
+                .0
+                .to_scale_rotation_translation();
+
+        let wall_hit_result = rapier_context
+            .cast_shape(
+                body_translation,
+                body_rotation,
+                forward,
+                character_body.1,
+                character
+                    .5
+                    .wall_contact_distance,
+                false,
+                QueryFilter::new().groups(
+                    CollisionGroups::new(
+                        Group::from_bits(
+                            0b0100,
+                        )
+                        .unwrap(),
+                        Group::from_bits(
+                            0b0010,
+                        )
+                        .unwrap(),
+                    ),
+                ),
+            );
+
+        if wall_hit_result.is_none() {
+            continue;
+        }
+
+        let (wall_entity, wall_hit) =
+            wall_hit_result.unwrap();
+
+        if wall_hit.details.is_none() {
+            continue;
+        }
+
+        let wall_hit_details =
+            wall_hit.details.unwrap();
+
+        if !is_wall_runnable(
+            wall_hit_details.normal1,
+            character.7.direction,
+            character.4,
+        ) {
+            continue;
+        }
+
+        stick_character_to_wall(
+            &mut commands,
+            character.0,
+            &mut character.2,
+            &mut character.3,
+            global_velocity,
+            wall_entity,
+            wall_hit_details.witness1,
+            wall_hit_details.normal1,
+        );
+
+        character.6.next_phase =
+            Some(CharacterPhase::WallRun);
+    }
+}
+
+/// system to start, keep or stop a character running along a wall, while on stage.
+/// ie, on a near-vertical stage, run while fast enough, otherwise detach and fall.
+pub fn update_character_wall_run_while_on_stage_system(
+    mut commands: Commands,
+    mut character_query: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut Velocity,
+            &mut CharacterMovementVariablesComponent,
+            &CharacterWallRunParametersComponent,
+            &mut CharacterPhaseVariablesComponent,
+            &CharacterGravityVariablesComponent,
+        ),
+        (
+            With<CharacterTagComponent>,
+            With<CharacterStageComponent>,
+        ),
+    >,
+) {
+    for mut character in
+        character_query.iter_mut()
+    {
+        if character
+            .5
+            .next_phase
+            .is_some()
+        {
+            continue;
+        }
+
+        let character_up =
+            *character.1.up();
+        let speed = character
+            .3
+            .global_horizontal_velocity
+            .length();
+        let is_on_wall = is_wall_runnable(
+            character_up,
+            character.6.direction,
+            character.4,
+        );
+
+        match character.5.phase {
+            CharacterPhase::WallRun => {
+                // ran onto a walkable surface
+                if !is_on_wall {
+                    character.5.next_phase = Some(
+                        CharacterPhase::Grounded,
+                    );
+
+                    continue;
+                }
+
+                if speed
+                    >= character
+                        .4
+                        .minimum_wall_run_speed
+                    && character.5.phase_time
+                        < character
+                            .4
+                            .maximum_wall_run_duration
+                {
+                    continue;
+                }
+            }
+            CharacterPhase::Grounded
+            | CharacterPhase::Landing => {
+                if !is_on_wall {
+                    continue;
+                }
+
+                // ran onto a wall from the stage
+                if speed
+                    >= character
+                        .4
+                        .minimum_entry_speed
+                {
+                    character.5.next_phase = Some(
+                        CharacterPhase::WallRun,
+                    );
+
+                    continue;
+                }
+            }
+            _ => {
+                continue;
+            }
+        }
+
+        // detach from the wall
+        let global_velocity =
+            find_global_velocity_while_on_stage(
+                character_up,
+                &character.3,
+            );
+
+        character
+            .3
+            .global_horizontal_velocity =
+            global_velocity.xz();
+        character
+            .3
+            .local_vertical_velocity =
+            global_velocity.y;
+        character.2.linvel =
+            global_velocity;
+
+        commands
+            .entity(character.0)
+            .remove::<CharacterStageComponent>();
+
+        character.5.next_phase =
+            Some(CharacterPhase::Falling);
+    }
+}
+
+/// system to hold a character teetering on the edge of the stage.
+/// ie, recover when moving away from the edge, or fall once the teeter window is over.
+pub fn update_character_body_while_ground_ledge_trip_system(
+    rapier_context: Res<RapierContext>,
+    mut commands: Commands,
+    mut character_query: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut Velocity,
+            &mut CharacterMovementVariablesComponent,
+            &CharacterPlayerInputComponent,
+            &CharacterLedgeTripParametersComponent,
+            &CharacterLedgeCatchParametersComponent,
+            &mut CharacterPhaseVariablesComponent,
+            &CharacterLedgeTripComponent,
+            &Children,
+            &CharacterMoveShapeParametersComponent,
+            &CharacterGravityVariablesComponent,
+        ),
+        With<CharacterTagComponent>,
+    >,
+    character_body_query: Query<
+        (Entity, &Transform, &Collider),
+        (
+            With<CharacterBodyTagComponent>,
+            Without<CharacterTagComponent>,
+        ),
+    >,
+) {
+    for mut character in
+        character_query.iter_mut()
+    {
+        if character.7.phase
+            != CharacterPhase::GroundLedgeTrip
+        {
+            // left the phase some other way
+            if character.7.next_phase
+                != Some(
+                    CharacterPhase::GroundLedgeTrip,
+                )
+            {
+                commands
+                    .entity(character.0)
+                    .remove::<CharacterLedgeTripComponent>();
+            }
+
+            continue;
+        }
+
+        if character
+            .7
+            .next_phase
+            .is_some()
+        {
+            continue;
+        }
+
+        let rotation_from_character_up_to_global_up =
             Quat::from_rotation_arc(
-                *character.4.up(),
-                ray_intersection.normal,
+                *character.1.up(),
+                Vec3::Y,
             );
-        character.4.rotation *=
-            rotation;
-        character.4.translation =
-            ray_intersection.point;
-        character.2.linvel.y = 0.0;
+        let local_edge_normal = Quat::mul_vec3(
+            rotation_from_character_up_to_global_up,
+            character.8.edge_normal,
+        )
+        .xz()
+        .normalize_or_zero();
+        let local_movement_input = Quat::mul_vec3(
+            rotation_from_character_up_to_global_up,
+            character
+                .4
+                .global_movement_player_input,
+        )
+        .xz();
+
+        // recover
+        if Vec2::dot(
+            local_movement_input,
+            local_edge_normal,
+        ) < 0.0
+        {
+            commands
+                .entity(character.0)
+                .remove::<CharacterLedgeTripComponent>();
+
+            character.7.next_phase =
+                Some(CharacterPhase::Grounded);
+
+            continue;
+        }
+
+        // held at the edge
+        let outward_speed = f32::max(
+            Vec3::dot(
+                character.2.linvel,
+                character.8.edge_normal,
+            ),
+            0.0,
+        );
+        character.2.linvel -= character
+            .8
+            .edge_normal
+            * outward_speed;
+        let local_outward_speed = f32::max(
+            Vec2::dot(
+                character
+                    .3
+                    .global_horizontal_velocity,
+                local_edge_normal,
+            ),
+            0.0,
+        );
+        character
+            .3
+            .global_horizontal_velocity -=
+            local_edge_normal
+                * local_outward_speed;
+
+        if character.7.phase_time
+            < character
+                .5
+                .teeter_duration
+        {
+            continue;
+        }
+
+        let character_body_result = character
+            .9
+            .iter()
+            .find_map(|child| {
+                character_body_query
+                    .get(*child)
+                    .ok()
+            });
+
+        if character_body_result.is_none() {
+            continue;
+        }
+
+        let character_body: (
+            Entity,
+            &Transform,
+            &Collider,
+        ) = character_body_result.unwrap();
+
+        // fall off the edge, across gravity
+        let gravity_up = find_gravity_up(
+            &character.1,
+            character.11,
+        );
+        let trip_fall_motion = character
+            .8
+            .edge_point
+            + character.8.edge_normal
+                * character
+                    .6
+                    .ledge_catch_margin
+            - character.1.translation;
+        move_character_body(
+            &rapier_context,
+            &mut character.1,
+            character_body.0,
+            character_body.1,
+            character_body.2,
+            trip_fall_motion,
+            gravity_up,
+            character.10,
+        );
+
+        // in the air, velocity is global
+        let trip_fall_velocity = (character
+            .8
+            .edge_normal
+            - gravity_up
+                * Vec3::dot(
+                    character.8.edge_normal,
+                    gravity_up,
+                ))
+        .normalize_or_zero()
+            * character.5.trip_fall_speed;
+        character
+            .3
+            .global_horizontal_velocity =
+            trip_fall_velocity.xz();
         character
             .3
             .local_vertical_velocity =
-            0.0;
+            trip_fall_velocity.y;
 
         commands
             .entity(character.0)
-            .insert(
-            CharacterStageComponent {
-                stage_entity:
-                    stage_entity,
-            },
+            .remove::<CharacterStageComponent>()
+            .remove::<CharacterLedgeTripComponent>();
+
+        character.7.next_phase =
+            Some(CharacterPhase::Falling);
+    }
+}
+
+/// system to move a character over the obstacle it is vaulting, then release it into the air.
+pub fn update_character_body_while_vault_system(
+    rapier_context: Res<RapierContext>,
+    mut commands: Commands,
+    mut character_query: Query<
+        (
+            Entity,
+            &mut Transform,
+            &mut Velocity,
+            &mut CharacterMovementVariablesComponent,
+            &mut CharacterPhaseVariablesComponent,
+            &CharacterVaultComponent,
+            &Children,
+            &CharacterMoveShapeParametersComponent,
+        ),
+        With<CharacterTagComponent>,
+    >,
+    character_body_query: Query<
+        (Entity, &Transform, &Collider),
+        (
+            With<CharacterBodyTagComponent>,
+            Without<CharacterTagComponent>,
+        ),
+    >,
+) {
+    for mut character in
+        character_query.iter_mut()
+    {
+        // moved along the arc, instead of by velocity
+        character.2.linvel = Vec3::ZERO;
+        character
+            .3
+            .global_horizontal_velocity =
+            Vec2::ZERO;
+        character
+            .3
+            .local_vertical_velocity = 0.0;
+
+        if character.4.phase
+            != CharacterPhase::Vault
+        {
+            continue;
+        }
+
+        let vault_progress = f32::clamp(
+            character.4.phase_time
+                / character
+                    .5
+                    .vault_duration,
+            0.0,
+            1.0,
         );
 
-        println!(
-            "land {}",
-            ray_intersection.point
+        let vault_position = Vec3::lerp(
+            character.5.start_position,
+            character.5.end_position,
+            vault_progress,
+        ) + character.5.up
+            * character.5.arc_height
+            * 4.0
+            * vault_progress
+            * (1.0 - vault_progress);
+
+        let character_body_result = character
+            .6
+            .iter()
+            .find_map(|child| {
+                character_body_query
+                    .get(*child)
+                    .ok()
+            });
+
+        if character_body_result.is_none() {
+            continue;
+        }
+
+        let character_body: (
+            Entity,
+            &Transform,
+            &Collider,
+        ) = character_body_result.unwrap();
+
+        // along the arc, catching up when an earlier move was blocked
+        let vault_motion = vault_position
+            - character.1.translation;
+        move_character_body(
+            &rapier_context,
+            &mut character.1,
+            character_body.0,
+            character_body.1,
+            character_body.2,
+            vault_motion,
+            character.5.up,
+            character.7,
         );
 
-        return;
+        if vault_progress < 1.0 {
+            continue;
+        }
+
+        character
+            .3
+            .global_horizontal_velocity =
+            character
+                .5
+                .global_horizontal_velocity;
+
+        commands
+            .entity(character.0)
+            .remove::<CharacterVaultComponent>();
+
+        character.4.next_phase =
+            Some(CharacterPhase::Falling);
+    }
+}
+
+/// system to log bonks of characters.
+pub fn log_character_bonk_system(
+    mut bonk_events: EventReader<
+        CharacterBonkEvent,
+    >,
+) {
+    for bonk_event in bonk_events.read()
+    {
+        debug!(
+            "{:?} bonk into {:?} at {} m/s, normal {}",
+            bonk_event.character_entity,
+            bonk_event.wall_entity,
+            bonk_event.impact_speed,
+            bonk_event.wall_normal
+        );
     }
 }
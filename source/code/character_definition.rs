@@ -0,0 +1,249 @@
+use bevy::{
+    asset::{
+        io::Reader, ron, Asset,
+        AssetEvent, AssetLoader,
+        AssetServer, Assets,
+        AsyncReadExt, Handle,
+        LoadContext,
+    },
+    ecs::{
+        component::Component,
+        event::EventReader,
+        query::With,
+        system::{
+            Commands, Query, Res,
+            Resource,
+        },
+    },
+    reflect::TypePath,
+    utils::BoxedFuture,
+};
+use serde::{Deserialize, Serialize};
+use std::io;
+
+use crate::character::{
+    CharacterAirOrientationParametersComponent,
+    CharacterBonkParametersComponent,
+    CharacterFallPhaseMovementParametersComponent,
+    CharacterLedgeCatchParametersComponent,
+    CharacterLedgeGetupParametersComponent,
+    CharacterLedgeTripParametersComponent,
+    CharacterMovementParametersComponent,
+    CharacterSlopeMovementParametersComponent,
+    CharacterStepParametersComponent,
+    CharacterTagComponent,
+    CharacterVaultParametersComponent,
+    CharacterWallRunParametersComponent,
+};
+use crate::character_move_shape::CharacterMoveShapeParametersComponent;
+
+/// path of the default character definition, relative to the assets folder.
+pub const CHARACTER_DEFINITION_PATH: &str =
+    "character/default.character.ron";
+
+/// asset with all tunable movement parameters of a character.
+/// ie, movement, jump, fall, snap, bonk, step, ledge getup, vault, ledge catch, ledge trip, wall run, air orientation and move shape parameters.
+#[derive(
+    Asset,
+    TypePath,
+    Serialize,
+    Deserialize,
+)]
+pub struct CharacterDefinitionAsset {
+    pub movement_parameters:
+        CharacterMovementParametersComponent,
+    pub slope_movement_parameters:
+        CharacterSlopeMovementParametersComponent,
+    pub fall_phase_movement_parameters:
+        CharacterFallPhaseMovementParametersComponent,
+    pub bonk_parameters:
+        CharacterBonkParametersComponent,
+    pub step_parameters:
+        CharacterStepParametersComponent,
+    pub ledge_getup_parameters:
+        CharacterLedgeGetupParametersComponent,
+    pub vault_parameters:
+        CharacterVaultParametersComponent,
+    pub ledge_catch_parameters:
+        CharacterLedgeCatchParametersComponent,
+    pub ledge_trip_parameters:
+        CharacterLedgeTripParametersComponent,
+    pub wall_run_parameters:
+        CharacterWallRunParametersComponent,
+    pub air_orientation_parameters:
+        CharacterAirOrientationParametersComponent,
+    pub move_shape_parameters:
+        CharacterMoveShapeParametersComponent,
+}
+
+/// asset loader for [CharacterDefinitionAsset] from ron files.
+#[derive(Default)]
+pub struct CharacterDefinitionAssetLoader;
+
+impl AssetLoader
+    for CharacterDefinitionAssetLoader
+{
+    type Asset = CharacterDefinitionAsset;
+    type Settings = ();
+    type Error = io::Error;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a Self::Settings,
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<
+        'a,
+        Result<Self::Asset, Self::Error>,
+    > {
+        return Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader
+                .read_to_end(&mut bytes)
+                .await?;
+
+            return ron::de::from_bytes::<
+                CharacterDefinitionAsset,
+            >(&bytes)
+            .map_err(|error| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    error,
+                )
+            });
+        });
+    }
+
+    fn extensions(&self) -> &[&str] {
+        return &["character.ron"];
+    }
+}
+
+/// resource with the handle of the character definition used when spawning characters.
+#[derive(Resource)]
+pub struct CharacterDefinitionResource {
+    pub handle:
+        Handle<CharacterDefinitionAsset>,
+}
+
+/// component with the character definition a character's parameters come from.
+#[derive(Component)]
+pub struct CharacterDefinitionVariablesComponent
+{
+    pub handle:
+        Handle<CharacterDefinitionAsset>,
+}
+
+/// system to start loading [CHARACTER_DEFINITION_PATH] into [CharacterDefinitionResource].
+pub fn load_character_definition_system(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+) {
+    commands.insert_resource(
+        CharacterDefinitionResource {
+            handle: asset_server.load(
+                CHARACTER_DEFINITION_PATH,
+            ),
+        },
+    );
+}
+
+/// system to copy parameters of a modified character definition to the characters using it.
+/// ie, hot reload of character definitions while playing.
+pub fn apply_modified_character_definition_to_characters_system(
+    mut asset_events: EventReader<
+        AssetEvent<
+            CharacterDefinitionAsset,
+        >,
+    >,
+    character_definitions: Res<
+        Assets<CharacterDefinitionAsset>,
+    >,
+    mut character_query: Query<
+        (
+            &CharacterDefinitionVariablesComponent,
+            &mut CharacterMovementParametersComponent,
+            &mut CharacterSlopeMovementParametersComponent,
+            &mut CharacterFallPhaseMovementParametersComponent,
+            &mut CharacterBonkParametersComponent,
+            &mut CharacterStepParametersComponent,
+            &mut CharacterLedgeGetupParametersComponent,
+            &mut CharacterVaultParametersComponent,
+            &mut CharacterLedgeCatchParametersComponent,
+            &mut CharacterLedgeTripParametersComponent,
+            &mut CharacterWallRunParametersComponent,
+            &mut CharacterAirOrientationParametersComponent,
+            &mut CharacterMoveShapeParametersComponent,
+        ),
+        With<CharacterTagComponent>,
+    >,
+) {
+    for asset_event in asset_events.read()
+    {
+        let AssetEvent::Modified { id } =
+            asset_event
+        else {
+            continue;
+        };
+
+        let character_definition_result =
+            character_definitions.get(*id);
+
+        if character_definition_result
+            .is_none()
+        {
+            continue;
+        }
+
+        let character_definition =
+            character_definition_result
+                .unwrap();
+
+        for mut character in
+            character_query.iter_mut()
+        {
+            if character.0.handle.id()
+                != *id
+            {
+                continue;
+            }
+
+            *character.1 = character_definition
+                .movement_parameters
+                .clone();
+            *character.2 = character_definition
+                .slope_movement_parameters
+                .clone();
+            *character.3 = character_definition
+                .fall_phase_movement_parameters
+                .clone();
+            *character.4 = character_definition
+                .bonk_parameters
+                .clone();
+            *character.5 = character_definition
+                .step_parameters
+                .clone();
+            *character.6 = character_definition
+                .ledge_getup_parameters
+                .clone();
+            *character.7 = character_definition
+                .vault_parameters
+                .clone();
+            *character.8 = character_definition
+                .ledge_catch_parameters
+                .clone();
+            *character.9 = character_definition
+                .ledge_trip_parameters
+                .clone();
+            *character.10 = character_definition
+                .wall_run_parameters
+                .clone();
+            *character.11 = character_definition
+                .air_orientation_parameters
+                .clone();
+            *character.12 = character_definition
+                .move_shape_parameters
+                .clone();
+        }
+    }
+}
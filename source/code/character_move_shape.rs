@@ -0,0 +1,757 @@
+use bevy::{
+    ecs::{
+        component::Component,
+        entity::Entity,
+        query::{Has, With, Without},
+        system::{Query, Res},
+    },
+    gizmos::gizmos::Gizmos,
+    hierarchy::Children,
+    math::{Quat, Vec3},
+    render::color::Color,
+    time::Time,
+    transform::components::Transform,
+};
+use bevy_rapier3d::{
+    dynamics::Velocity,
+    geometry::{
+        Collider, CollisionGroups, Group,
+    },
+    pipeline::QueryFilter,
+    plugin::RapierContext,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    character::{
+        CharacterBodyTagComponent,
+        CharacterStageComponent,
+        CharacterTagComponent,
+    },
+    gravity::CharacterGravityVariablesComponent,
+};
+
+/// hit of a shape against a collider, while moving the shape.
+#[derive(Clone, Copy)]
+pub struct MoveShapeHit {
+    pub entity: Entity,
+
+    /// global outward normal of the hit collider.
+    pub normal: Vec3,
+
+    /// global position of the hit on the hit collider.
+    pub point: Vec3,
+
+    /// distance moved along the motion of the iteration before the hit, in m.
+    /// ie, without the skin width. for ground, the distance of the ground below the shape.
+    pub time_of_impact: f32,
+}
+
+/// results of moving a shape through the world.
+#[derive(Clone, Default)]
+pub struct MoveShapeResult {
+    /// global position of the shape after moving.
+    pub translation: Vec3,
+
+    /// hits while moving, in order.
+    pub hits: Vec<MoveShapeHit>,
+
+    /// motion left after the last iteration, in m.
+    /// ie, non-zero when sliding ran out of iterations.
+    pub slide_remainder: Vec3,
+
+    /// walkable ground below the shape after moving.
+    pub ground: Option<MoveShapeHit>,
+}
+
+/// component with parameters for moving a character body through the world.
+#[derive(
+    Component,
+    Clone,
+    Serialize,
+    Deserialize,
+)]
+pub struct CharacterMoveShapeParametersComponent
+{
+    /// distance kept between the body and colliders, in m.
+    /// ie, avoids starting the next move penetrating.
+    pub skin_width: f32,
+
+    /// maximum number of times to slide along colliders in one move.
+    pub maximum_slide_iterations: u32,
+
+    /// maximum angle, in radians, between up and a ground normal for the ground to be walkable.
+    pub maximum_ground_angle: f32,
+
+    /// distance below the body to look for ground after moving, in m.
+    pub ground_probe_distance: f32,
+}
+
+/// component with the results of the last move of a character body.
+#[derive(Component, Default)]
+pub struct CharacterMoveShapeVariablesComponent
+{
+    pub result: MoveShapeResult,
+}
+
+/// find the motion left to slide along a hit, from the motion left after the hit.
+/// ie, removes the part of the motion into the hit, and keeps the part along or away from it.
+pub fn find_slide_motion(
+    motion: Vec3,
+    hit_normal: Vec3,
+) -> Vec3 {
+    return motion
+        - hit_normal
+            * f32::min(
+                Vec3::dot(motion, hit_normal),
+                0.0,
+            );
+}
+
+/// if a surface is shallow enough to stand on.
+pub fn is_walkable_ground(
+    surface_normal: Vec3,
+    up: Vec3,
+    maximum_ground_angle: f32,
+) -> bool {
+    return Vec3::angle_between(
+        surface_normal,
+        up,
+    ) <= maximum_ground_angle;
+}
+
+/// move a shape by a motion, colliding with and sliding along colliders.
+/// ie, collide and slide.
+#[allow(clippy::too_many_arguments)]
+pub fn move_shape(
+    rapier_context: &RapierContext,
+    shape: &Collider,
+    translation: Vec3,
+    rotation: Quat,
+    motion: Vec3,
+    up: Vec3,
+    filter: QueryFilter,
+    parameters: &CharacterMoveShapeParametersComponent,
+) -> MoveShapeResult {
+    let mut next_translation =
+        translation;
+    let mut remaining_motion = motion;
+    let mut hits: Vec<MoveShapeHit> =
+        Vec::new();
+
+    for _ in 0..parameters
+        .maximum_slide_iterations
+    {
+        let distance =
+            remaining_motion.length();
+
+        if distance <= 0.0 {
+            break;
+        }
+
+        let direction =
+            remaining_motion / distance;
+
+        let hit_result = rapier_context
+            .cast_shape(
+                next_translation,
+                rotation,
+                direction,
+                shape,
+                distance
+                    + parameters.skin_width,
+                false,
+                filter,
+            );
+
+        if hit_result.is_none() {
+            next_translation +=
+                remaining_motion;
+            remaining_motion = Vec3::ZERO;
+
+            break;
+        }
+
+        let (hit_entity, hit) =
+            hit_result.unwrap();
+
+        // penetrating, without a normal to slide along
+        if hit.details.is_none() {
+            break;
+        }
+
+        let hit_details =
+            hit.details.unwrap();
+
+        let travel_distance = f32::clamp(
+            hit.toi - parameters.skin_width,
+            0.0,
+            distance,
+        );
+
+        next_translation +=
+            direction * travel_distance;
+
+        hits.push(MoveShapeHit {
+            entity: hit_entity,
+            normal: hit_details.normal1,
+            point: hit_details.witness1,
+            time_of_impact: travel_distance,
+        });
+
+        // slide the rest of the motion along the hit
+        remaining_motion = find_slide_motion(
+            direction
+                * (distance
+                    - travel_distance),
+            hit_details.normal1,
+        );
+    }
+
+    // walkable ground below
+    let ground = rapier_context
+        .cast_shape(
+            next_translation,
+            rotation,
+            -up,
+            shape,
+            parameters.ground_probe_distance,
+            false,
+            filter,
+        )
+        .and_then(|(ground_entity, ground_hit)| {
+            let ground_details =
+                ground_hit.details?;
+
+            if !is_walkable_ground(
+                ground_details.normal1,
+                up,
+                parameters
+                    .maximum_ground_angle,
+            ) {
+                return None;
+            }
+
+            return Some(MoveShapeHit {
+                entity: ground_entity,
+                normal: ground_details.normal1,
+                point: ground_details.witness1,
+                time_of_impact: ground_hit.toi,
+            });
+        });
+
+    return MoveShapeResult {
+        translation: next_translation,
+        hits,
+        slide_remainder: remaining_motion,
+        ground,
+    };
+}
+
+/// move a character by a global motion, by moving its body through the world.
+/// ie, traversal moves are swept, instead of teleporting the character through colliders.
+/// nb, ground is looked for opposite of up.
+#[allow(clippy::too_many_arguments)]
+pub fn move_character_body(
+    rapier_context: &RapierContext,
+    character_transform: &mut Transform,
+    body_entity: Entity,
+    body_transform: &Transform,
+    body_collider: &Collider,
+    motion: Vec3,
+    up: Vec3,
+    parameters: &CharacterMoveShapeParametersComponent,
+) -> MoveShapeResult {
+    // from the character transform, as global transforms are not propagated yet
+    let body_translation =
+        character_transform
+            .transform_point(
+                body_transform.translation,
+            );
+    let body_rotation =
+        character_transform.rotation
+            * body_transform.rotation;
+
+    let result = move_shape(
+        rapier_context,
+        body_collider,
+        body_translation,
+        body_rotation,
+        motion,
+        up,
+        QueryFilter::new()
+            .groups(CollisionGroups::new(
+                Group::from_bits(0b0100)
+                    .unwrap(),
+                Group::from_bits(0b0110)
+                    .unwrap(),
+            ))
+            .exclude_collider(body_entity),
+        parameters,
+    );
+
+    character_transform.translation +=
+        result.translation
+            - body_translation;
+
+    return result;
+}
+
+/// system to move a character by its body velocity, by moving its body through the world.
+/// ie, the kinematic replacement for integrating a dynamic rigid body.
+pub fn update_character_body_using_move_shape_system(
+    time: Res<Time>,
+    rapier_context: Res<RapierContext>,
+    mut character_query: Query<
+        (
+            &Children,
+            &mut Transform,
+            &mut Velocity,
+            &CharacterMoveShapeParametersComponent,
+            &mut CharacterMoveShapeVariablesComponent,
+            Has<CharacterStageComponent>,
+            &CharacterGravityVariablesComponent,
+        ),
+        With<CharacterTagComponent>,
+    >,
+    character_body_query: Query<
+        (Entity, &Transform, &Collider),
+        (
+            With<CharacterBodyTagComponent>,
+            Without<CharacterTagComponent>,
+        ),
+    >,
+) {
+    for mut character in
+        character_query.iter_mut()
+    {
+        let character_body_result = character
+            .0
+            .iter()
+            .find_map(|child| {
+                character_body_query
+                    .get(*child)
+                    .ok()
+            });
+
+        if character_body_result.is_none() {
+            continue;
+        }
+
+        let character_body: (
+            Entity,
+            &Transform,
+            &Collider,
+        ) = character_body_result.unwrap();
+
+        let motion = character.2.linvel
+            * time.delta_seconds();
+
+        // on stage, ground is below the character. in the air, ground is along gravity
+        let ground_up = if character.5
+            || character.6.direction
+                == Vec3::ZERO
+        {
+            *character.1.up()
+        } else {
+            -character.6.direction
+        };
+
+        let result = move_character_body(
+            &rapier_context,
+            &mut character.1,
+            character_body.0,
+            character_body.1,
+            character_body.2,
+            motion,
+            ground_up,
+            character.3,
+        );
+
+        // cancel velocity into hit colliders
+        for hit in result.hits.iter() {
+            character.2.linvel =
+                find_slide_motion(
+                    character.2.linvel,
+                    hit.normal,
+                );
+        }
+
+        character.4.result = result;
+    }
+}
+
+/// system to draw the results of the last move of each character.
+/// ie, hit normals in red, ground normal in green, distance of the ground below the body along the ground normal in blue, slide remainder in yellow.
+pub fn draw_character_move_shape_gizmos_system(
+    mut gizmos: Gizmos,
+    character_query: Query<
+        &CharacterMoveShapeVariablesComponent,
+        With<CharacterTagComponent>,
+    >,
+) {
+    for move_shape_variables in
+        character_query.iter()
+    {
+        let result =
+            &move_shape_variables.result;
+
+        for hit in result.hits.iter() {
+            gizmos.arrow(
+                hit.point,
+                hit.point + hit.normal * 0.5,
+                Color::RED,
+            );
+        }
+
+        if let Some(ground) = result.ground
+        {
+            gizmos.arrow(
+                ground.point,
+                ground.point
+                    + ground.normal * 0.5,
+                Color::GREEN,
+            );
+            gizmos.line(
+                ground.point,
+                ground.point
+                    + ground.normal
+                        * ground.time_of_impact,
+                Color::BLUE,
+            );
+        }
+
+        gizmos.line(
+            result.translation,
+            result.translation
+                + result.slide_remainder,
+            Color::YELLOW,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::{
+        ecs::entity::Entity,
+        math::{Quat, Vec3},
+    };
+    use bevy_rapier3d::{
+        geometry::Collider,
+        pipeline::QueryFilter,
+        plugin::RapierContext,
+        rapier::{
+            geometry::ColliderBuilder,
+            math::Vector,
+        },
+    };
+
+    use super::{
+        find_slide_motion,
+        is_walkable_ground, move_shape,
+        CharacterMoveShapeParametersComponent,
+    };
+
+    fn move_shape_parameters(
+        maximum_slide_iterations: u32,
+    ) -> CharacterMoveShapeParametersComponent
+    {
+        return CharacterMoveShapeParametersComponent {
+            skin_width: 0.05,
+            maximum_slide_iterations,
+            maximum_ground_angle: 50.0_f32
+                .to_radians(),
+            ground_probe_distance: 0.5,
+        };
+    }
+
+    /// rapier context with one cuboid collider.
+    fn rapier_context_with_cuboid(
+        translation: Vec3,
+        half_extents: Vec3,
+    ) -> RapierContext {
+        let mut rapier_context =
+            RapierContext::default();
+        rapier_context.colliders.insert(
+            ColliderBuilder::new(
+                Collider::cuboid(
+                    half_extents.x,
+                    half_extents.y,
+                    half_extents.z,
+                )
+                .raw,
+            )
+            .translation(Vector::new(
+                translation.x,
+                translation.y,
+                translation.z,
+            ))
+            .user_data(
+                Entity::from_raw(1).to_bits()
+                    as u128,
+            )
+            .build(),
+        );
+        rapier_context.query_pipeline.update(
+            &rapier_context.bodies,
+            &rapier_context.colliders,
+        );
+
+        return rapier_context;
+    }
+
+    /// wall with its face towards the origin at x 2.5.
+    fn rapier_context_with_wall(
+    ) -> RapierContext {
+        return rapier_context_with_cuboid(
+            Vec3::new(3.0, 0.0, 0.0),
+            Vec3::new(0.5, 5.0, 50.0),
+        );
+    }
+
+    #[test]
+    fn move_shape_without_hits_moves_by_the_whole_motion(
+    ) {
+        let result = move_shape(
+            &RapierContext::default(),
+            &Collider::ball(0.5),
+            Vec3::ZERO,
+            Quat::IDENTITY,
+            Vec3::new(1.0, 2.0, 3.0),
+            Vec3::Y,
+            QueryFilter::new(),
+            &move_shape_parameters(4),
+        );
+
+        assert!(result.translation.abs_diff_eq(
+            Vec3::new(1.0, 2.0, 3.0),
+            1e-6
+        ));
+        assert!(result.hits.is_empty());
+        assert!(result
+            .slide_remainder
+            .abs_diff_eq(Vec3::ZERO, 1e-6));
+        assert!(result.ground.is_none());
+    }
+
+    #[test]
+    fn move_shape_into_hit_stops_at_skin_width(
+    ) {
+        let result = move_shape(
+            &rapier_context_with_wall(),
+            &Collider::ball(0.5),
+            Vec3::ZERO,
+            Quat::IDENTITY,
+            Vec3::new(5.0, 0.0, 0.0),
+            Vec3::Y,
+            QueryFilter::new(),
+            &move_shape_parameters(4),
+        );
+
+        // the ball touches the wall at x 2.0
+        assert!(result.translation.abs_diff_eq(
+            Vec3::new(1.95, 0.0, 0.0),
+            1e-3
+        ));
+        assert_eq!(result.hits.len(), 1);
+        assert!(result.hits[0]
+            .normal
+            .abs_diff_eq(Vec3::NEG_X, 1e-3));
+        assert!(
+            (result.hits[0].time_of_impact
+                - 1.95)
+                .abs()
+                < 1e-3
+        );
+        assert!(result
+            .slide_remainder
+            .abs_diff_eq(Vec3::ZERO, 1e-6));
+    }
+
+    #[test]
+    fn move_shape_slides_along_hit(
+    ) {
+        let result = move_shape(
+            &rapier_context_with_wall(),
+            &Collider::ball(0.5),
+            Vec3::ZERO,
+            Quat::IDENTITY,
+            Vec3::new(4.0, 0.0, 4.0),
+            Vec3::Y,
+            QueryFilter::new(),
+            &move_shape_parameters(4),
+        );
+
+        // stopped at the skin width along the diagonal, then slid along the wall for the rest
+        assert!(result.translation.abs_diff_eq(
+            Vec3::new(1.9646, 0.0, 4.0),
+            1e-3
+        ));
+        assert_eq!(result.hits.len(), 1);
+        assert!(result
+            .slide_remainder
+            .abs_diff_eq(Vec3::ZERO, 1e-6));
+    }
+
+    #[test]
+    fn move_shape_keeps_slide_remainder_when_out_of_iterations(
+    ) {
+        let result = move_shape(
+            &rapier_context_with_wall(),
+            &Collider::ball(0.5),
+            Vec3::ZERO,
+            Quat::IDENTITY,
+            Vec3::new(4.0, 0.0, 4.0),
+            Vec3::Y,
+            QueryFilter::new(),
+            &move_shape_parameters(1),
+        );
+
+        assert!(result.translation.abs_diff_eq(
+            Vec3::new(1.9646, 0.0, 1.9646),
+            1e-3
+        ));
+        assert!(result
+            .slide_remainder
+            .abs_diff_eq(
+                Vec3::new(0.0, 0.0, 2.0355),
+                1e-3
+            ));
+    }
+
+    #[test]
+    fn move_shape_penetrating_does_not_move_deeper(
+    ) {
+        let translation =
+            Vec3::new(2.4, 0.0, 0.0);
+        let result = move_shape(
+            &rapier_context_with_wall(),
+            &Collider::ball(0.5),
+            translation,
+            Quat::IDENTITY,
+            Vec3::new(1.0, 0.0, 0.0),
+            Vec3::Y,
+            QueryFilter::new(),
+            &move_shape_parameters(4),
+        );
+
+        assert!(result
+            .translation
+            .abs_diff_eq(translation, 1e-6));
+    }
+
+    #[test]
+    fn move_shape_finds_walkable_ground_below(
+    ) {
+        // floor with its top at y 0
+        let result = move_shape(
+            &rapier_context_with_cuboid(
+                Vec3::new(0.0, -0.5, 0.0),
+                Vec3::new(5.0, 0.5, 5.0),
+            ),
+            &Collider::ball(0.5),
+            Vec3::new(0.0, 0.6, 0.0),
+            Quat::IDENTITY,
+            Vec3::ZERO,
+            Vec3::Y,
+            QueryFilter::new(),
+            &move_shape_parameters(4),
+        );
+
+        let ground = result.ground.unwrap();
+        assert!(ground
+            .normal
+            .abs_diff_eq(Vec3::Y, 1e-3));
+        assert!(
+            (ground.time_of_impact - 0.1)
+                .abs()
+                < 1e-3
+        );
+    }
+
+    #[test]
+    fn slide_motion_into_hit_is_cancelled() {
+        let slide_motion = find_slide_motion(
+            Vec3::new(0.0, 0.0, -2.0),
+            Vec3::Z,
+        );
+
+        assert!(slide_motion
+            .abs_diff_eq(Vec3::ZERO, 1e-6));
+    }
+
+    #[test]
+    fn slide_motion_keeps_motion_along_hit() {
+        let slide_motion = find_slide_motion(
+            Vec3::new(1.0, 0.5, -2.0),
+            Vec3::Z,
+        );
+
+        assert!(slide_motion.abs_diff_eq(
+            Vec3::new(1.0, 0.5, 0.0),
+            1e-6
+        ));
+    }
+
+    #[test]
+    fn slide_motion_away_from_hit_is_unchanged(
+    ) {
+        let motion =
+            Vec3::new(1.0, 0.0, 2.0);
+        let slide_motion = find_slide_motion(
+            motion, Vec3::Z,
+        );
+
+        assert!(slide_motion
+            .abs_diff_eq(motion, 1e-6));
+    }
+
+    #[test]
+    fn slide_motion_along_slope_is_perpendicular_to_normal(
+    ) {
+        let hit_normal =
+            Vec3::new(0.0, 1.0, 1.0)
+                .normalize();
+        let slide_motion = find_slide_motion(
+            Vec3::new(0.0, -1.0, -3.0),
+            hit_normal,
+        );
+
+        assert!(
+            Vec3::dot(
+                slide_motion,
+                hit_normal
+            )
+            .abs()
+                < 1e-6
+        );
+        assert!(slide_motion.abs_diff_eq(
+            Vec3::new(0.0, 1.0, -1.0),
+            1e-6
+        ));
+    }
+
+    #[test]
+    fn walkable_ground_is_within_maximum_angle(
+    ) {
+        let maximum_ground_angle =
+            50.0_f32.to_radians();
+
+        assert!(is_walkable_ground(
+            Vec3::Y,
+            Vec3::Y,
+            maximum_ground_angle
+        ));
+        assert!(is_walkable_ground(
+            Vec3::new(0.0, 1.0, 1.0)
+                .normalize(),
+            Vec3::Y,
+            maximum_ground_angle
+        ));
+        assert!(!is_walkable_ground(
+            Vec3::Z,
+            Vec3::Y,
+            maximum_ground_angle
+        ));
+    }
+}
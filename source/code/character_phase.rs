@@ -0,0 +1,403 @@
+use bevy::{
+    ecs::{
+        component::Component,
+        entity::Entity,
+        event::{Event, EventReader, EventWriter},
+        query::{Has, With},
+        system::{Query, Res},
+    },
+    log::debug,
+    math::Vec3,
+    time::Time,
+};
+
+use crate::{
+    character::{
+        CharacterBonkParametersComponent,
+        CharacterMovementVariablesComponent,
+        CharacterStageComponent,
+        CharacterTagComponent,
+    },
+    gravity::CharacterGravityVariablesComponent,
+};
+
+/// phase of a character.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CharacterPhase {
+    /// on the stage.
+    Grounded,
+    /// in the air, moving upwards after a jump.
+    Jumping,
+    /// in the air, without a jump.
+    Falling,
+    /// on the stage, for the update after landing.
+    Landing,
+    /// on the stage, after running into a wall.
+    /// ie, input is locked out.
+    Bonk,
+    /// hanging from and pulling up onto a ledge, after catching it in the air.
+    LedgeGetup,
+    /// in the air, vaulting over an obstacle after running into it.
+    Vault,
+    /// on the stage, teetering on the edge of the stage.
+    /// aka "otto".
+    GroundLedgeTrip,
+    /// on a near-vertical stage, running along a wall.
+    WallRun,
+}
+
+impl CharacterPhase {
+    /// if characters in this phase are on the stage.
+    /// ie, they have a [CharacterStageComponent].
+    pub fn is_on_stage(self) -> bool {
+        return matches!(
+            self,
+            CharacterPhase::Grounded
+                | CharacterPhase::Landing
+                | CharacterPhase::Bonk
+                | CharacterPhase::GroundLedgeTrip
+                | CharacterPhase::WallRun
+        );
+    }
+
+    /// if the transition from this phase to next phase is allowed.
+    pub fn can_transition_to(
+        self,
+        next_phase: CharacterPhase,
+    ) -> bool {
+        return matches!(
+            (self, next_phase),
+            (
+                CharacterPhase::Grounded,
+                CharacterPhase::Jumping
+                    | CharacterPhase::Falling
+                    | CharacterPhase::Bonk
+                    | CharacterPhase::Vault
+                    | CharacterPhase::GroundLedgeTrip
+                    | CharacterPhase::WallRun
+            ) | (
+                CharacterPhase::Jumping,
+                CharacterPhase::Falling
+                    | CharacterPhase::Landing
+                    | CharacterPhase::LedgeGetup
+                    | CharacterPhase::WallRun
+            ) | (
+                CharacterPhase::Falling,
+                CharacterPhase::Landing
+                    | CharacterPhase::LedgeGetup
+                    | CharacterPhase::WallRun
+            ) | (
+                CharacterPhase::Landing,
+                CharacterPhase::Grounded
+                    | CharacterPhase::Jumping
+                    | CharacterPhase::Falling
+                    | CharacterPhase::Bonk
+                    | CharacterPhase::Vault
+                    | CharacterPhase::GroundLedgeTrip
+                    | CharacterPhase::WallRun
+            ) | (
+                CharacterPhase::Bonk,
+                CharacterPhase::Grounded
+                    | CharacterPhase::Falling
+            ) | (
+                CharacterPhase::LedgeGetup,
+                CharacterPhase::Landing
+            ) | (
+                CharacterPhase::Vault,
+                CharacterPhase::Falling
+            ) | (
+                CharacterPhase::GroundLedgeTrip,
+                CharacterPhase::Grounded
+                    | CharacterPhase::Jumping
+                    | CharacterPhase::Falling
+            ) | (
+                CharacterPhase::WallRun,
+                CharacterPhase::Grounded
+                    | CharacterPhase::Jumping
+                    | CharacterPhase::Falling
+            )
+        );
+    }
+}
+
+/// component with variables for the phase of a character.
+#[derive(Component)]
+pub struct CharacterPhaseVariablesComponent
+{
+    /// current phase.
+    pub phase: CharacterPhase,
+
+    /// phase to transition to, in [transition_character_phase_system].
+    /// ie, systems request a transition by setting this.
+    pub next_phase:
+        Option<CharacterPhase>,
+
+    /// time spent in the current phase, in seconds.
+    pub phase_time: f32,
+}
+
+/// event for a character exiting a phase.
+#[derive(Event)]
+pub struct CharacterPhaseExitEvent {
+    pub character_entity: Entity,
+    pub phase: CharacterPhase,
+    pub next_phase: CharacterPhase,
+    /// time spent in the exited phase, in seconds.
+    pub phase_time: f32,
+}
+
+/// event for a character entering a phase.
+#[derive(Event)]
+pub struct CharacterPhaseEnterEvent {
+    pub character_entity: Entity,
+    pub phase: CharacterPhase,
+    pub previous_phase: CharacterPhase,
+}
+
+// region transition system
+
+/// system to request [CharacterPhase::Falling] for a jumping character which is no longer moving against gravity.
+pub fn update_character_phase_while_jumping_system(
+    mut character_query: Query<
+        (
+            &CharacterMovementVariablesComponent,
+            &mut CharacterPhaseVariablesComponent,
+            &CharacterGravityVariablesComponent,
+        ),
+        With<CharacterTagComponent>,
+    >,
+) {
+    for mut character in
+        character_query.iter_mut()
+    {
+        if character.1.phase
+            != CharacterPhase::Jumping
+        {
+            continue;
+        }
+
+        // in the air, velocity is global
+        let global_velocity = Vec3::new(
+            character
+                .0
+                .global_horizontal_velocity
+                .x,
+            character
+                .0
+                .local_vertical_velocity,
+            character
+                .0
+                .global_horizontal_velocity
+                .y,
+        );
+
+        if Vec3::dot(
+            global_velocity,
+            -character.2.direction,
+        ) > 0.0
+        {
+            continue;
+        }
+
+        character.1.next_phase =
+            Some(CharacterPhase::Falling);
+    }
+}
+
+/// system to request [CharacterPhase::Grounded] for a character which has landed.
+pub fn update_character_phase_while_landing_system(
+    mut character_query: Query<
+        (&mut CharacterPhaseVariablesComponent,),
+        With<CharacterTagComponent>,
+    >,
+) {
+    for mut character in
+        character_query.iter_mut()
+    {
+        if character.0.phase
+            != CharacterPhase::Landing
+        {
+            continue;
+        }
+
+        if character.0.next_phase.is_some()
+        {
+            continue;
+        }
+
+        character.0.next_phase =
+            Some(CharacterPhase::Grounded);
+    }
+}
+
+/// system to request [CharacterPhase::Grounded] for a bonked character once input lockout is over.
+pub fn update_character_phase_while_bonk_system(
+    mut character_query: Query<
+        (
+            &CharacterBonkParametersComponent,
+            &mut CharacterPhaseVariablesComponent,
+        ),
+        With<CharacterTagComponent>,
+    >,
+) {
+    for mut character in
+        character_query.iter_mut()
+    {
+        if character.1.phase
+            != CharacterPhase::Bonk
+        {
+            continue;
+        }
+
+        if character.1.next_phase.is_some()
+        {
+            continue;
+        }
+
+        if character.1.phase_time
+            < character.0.bonk_duration
+        {
+            continue;
+        }
+
+        character.1.next_phase =
+            Some(CharacterPhase::Grounded);
+    }
+}
+
+/// system to transition [CharacterPhaseVariablesComponent] to the requested next phase, when allowed.
+/// nb, [CharacterStageComponent] is the source of truth for being on the stage.
+/// ie, when the phase disagrees with it, the phase is forced to [CharacterPhase::Landing] or [CharacterPhase::Falling].
+pub fn transition_character_phase_system(
+    time: Res<Time>,
+    mut exit_events: EventWriter<
+        CharacterPhaseExitEvent,
+    >,
+    mut enter_events: EventWriter<
+        CharacterPhaseEnterEvent,
+    >,
+    mut character_query: Query<
+        (
+            Entity,
+            &mut CharacterPhaseVariablesComponent,
+            Has<CharacterStageComponent>,
+        ),
+        With<CharacterTagComponent>,
+    >,
+) {
+    for (
+        character_entity,
+        mut phase_variables,
+        is_on_stage,
+    ) in character_query.iter_mut()
+    {
+        phase_variables.phase_time +=
+            time.delta_seconds();
+
+        let previous_phase =
+            phase_variables.phase;
+
+        let mut next_phase_option =
+            phase_variables
+                .next_phase
+                .take()
+                .filter(|next_phase| {
+                    if previous_phase
+                        .can_transition_to(
+                            *next_phase,
+                        )
+                    {
+                        return true;
+                    }
+
+                    debug!(
+                        "{:?} rejected transition from {:?} to {:?}",
+                        character_entity,
+                        previous_phase,
+                        next_phase
+                    );
+                    return false;
+                });
+
+        // disagrees with the stage
+        if next_phase_option
+            .unwrap_or(previous_phase)
+            .is_on_stage()
+            != is_on_stage
+        {
+            next_phase_option =
+                Some(if is_on_stage {
+                    CharacterPhase::Landing
+                } else {
+                    CharacterPhase::Falling
+                });
+        }
+
+        if next_phase_option.is_none() {
+            continue;
+        }
+
+        let next_phase =
+            next_phase_option.unwrap();
+
+        exit_events.send(
+            CharacterPhaseExitEvent {
+                character_entity,
+                phase: previous_phase,
+                next_phase,
+                phase_time: phase_variables
+                    .phase_time,
+            },
+        );
+
+        phase_variables.phase =
+            next_phase;
+        phase_variables.phase_time = 0.0;
+
+        enter_events.send(
+            CharacterPhaseEnterEvent {
+                character_entity,
+                phase: next_phase,
+                previous_phase,
+            },
+        );
+    }
+}
+
+// endregion
+
+// region debug systems
+
+/// system to log phase transitions of characters.
+pub fn log_character_phase_transition_system(
+    mut exit_events: EventReader<
+        CharacterPhaseExitEvent,
+    >,
+    mut enter_events: EventReader<
+        CharacterPhaseEnterEvent,
+    >,
+) {
+    for exit_event in exit_events.read()
+    {
+        debug!(
+            "{:?} exit {:?} after {}s, to {:?}",
+            exit_event.character_entity,
+            exit_event.phase,
+            exit_event.phase_time,
+            exit_event.next_phase
+        );
+    }
+
+    for enter_event in
+        enter_events.read()
+    {
+        debug!(
+            "{:?} enter {:?}, from {:?}",
+            enter_event.character_entity,
+            enter_event.phase,
+            enter_event.previous_phase
+        );
+    }
+}
+
+// endregion
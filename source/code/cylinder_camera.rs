@@ -3,25 +3,47 @@ use bevy::{
         bundle::Bundle,
         component::Component,
         entity::Entity,
-        event::EventReader,
-        query::With,
-        system::{Query, Res},
+        query::{With, Without},
+        system::{Commands, Query, Res},
     },
     gizmos::gizmos::Gizmos,
-    input::mouse::{
-        MouseMotion, MouseWheel,
+    hierarchy::{
+        Children, HierarchyQueryExt,
+    },
+    math::{Quat, Vec3},
+    render::{
+        color::Color,
+        view::{Layer, RenderLayers},
     },
-    math::{Quat, Vec2, Vec3},
-    render::color::Color,
     time::Time,
     transform::components::{
         GlobalTransform, Transform,
     },
+    utils::HashMap,
+};
+use bevy_rapier3d::{
+    dynamics::Velocity,
+    geometry::{
+        Collider, CollisionGroups, Group,
+    },
+    pipeline::QueryFilter,
+    plugin::RapierContext,
 };
+use std::f32::consts::{PI, TAU};
 
-use crate::math::{
-    CylindricalCoordinates,
-    FromCylindrical, Slerp,
+use crate::{
+    camera_rail::{
+        CameraRailVariablesComponent,
+        SetDesiredTransformTranslationUsingCameraRailBehaviorComponent,
+    },
+    camera_zone::{
+        CameraZoneVariablesComponent,
+        SetDesiredTransformUsingCameraZoneBehaviorComponent,
+    },
+    math::{
+        CylindricalCoordinates,
+        FromCylindrical, SmoothDamp,
+    },
 };
 
 /// Tag component for "camera eyes" entity.
@@ -37,6 +59,8 @@ pub struct ActorCameraBundle {
     pub lookat_variables: LookatVariablesComponent,
     pub desired_lookat_variables: DesiredLookatVariablesComponent,
     pub lookat_offset_variables: LookatOffsetVariablesComponent,
+    pub transition_parameters: CameraTransitionParametersComponent,
+    pub transition_variables: CameraTransitionVariablesComponent,
 }
 
 /// bundle for "cylinder actor camera" entity
@@ -55,7 +79,45 @@ pub struct CylinderActorCameraBundle {
         SetDesiredLookatPositionToObservedEntityTransformTranslationWithOffsetBehaviorComponent,
   pub set_desired_lookat_up_to_observed_entity_transform_local_up_with_offset_behavior:
         SetDesiredLookatUpToObservedEntityTransformLocalUpWithOffsetBehaviorComponent,
-  pub set_lookat_offset_using_input_behavior: SetLookatOffsetUsingInputBehaviorComponent
+  pub set_lookat_offset_using_input_behavior: SetLookatOffsetUsingInputBehaviorComponent,
+  pub player_input: CameraPlayerInputComponent,
+  pub cylinder_transition_parameters: CylinderTransitionParametersComponent,
+  pub collision_parameters: CameraCollisionParametersComponent,
+  pub collision_variables: CameraCollisionVariablesComponent,
+  pub set_transform_translation_using_collision_behavior: SetTransformTranslationUsingCollisionBehaviorComponent,
+  pub camera_zone_variables: CameraZoneVariablesComponent,
+  pub set_desired_transform_using_camera_zone_behavior: SetDesiredTransformUsingCameraZoneBehaviorComponent,
+  pub camera_rail_variables: CameraRailVariablesComponent,
+  pub set_desired_transform_translation_using_camera_rail_behavior: SetDesiredTransformTranslationUsingCameraRailBehaviorComponent,
+  pub auto_follow_parameters: CameraAutoFollowParametersComponent,
+  pub auto_follow_variables: CameraAutoFollowVariablesComponent,
+  pub set_cylinder_coordinates_for_desired_transform_translation_using_auto_follow_behavior: SetCylinderCoordinatesForDesiredTransformTranslationUsingAutoFollowBehaviorComponent,
+}
+
+/// bundle with the behaviors of "cylinder actor camera" entity.
+/// ie, removed and inserted to switch between camera modes.
+#[derive(Bundle, Default)]
+pub struct CylinderActorCameraBehaviorBundle {
+    pub set_desired_parent_transform_translation_to_observed_entity_transform_behavior:
+        SetDesiredTransformTranslationToObservedEntityTransformTranslationBehaviorComponent,
+    pub set_desired_parent_transform_rotation_to_observed_entity_local_up_behavior:
+        SetDesiredTransformRotationToObservedEntityLocalUpBehaviorComponent,
+    pub set_desired_lookat_position_to_observed_entity_transform_translation_with_offset_behavior:
+        SetDesiredLookatPositionToObservedEntityTransformTranslationWithOffsetBehaviorComponent,
+    pub set_desired_lookat_up_to_observed_entity_transform_local_up_with_offset_behavior:
+        SetDesiredLookatUpToObservedEntityTransformLocalUpWithOffsetBehaviorComponent,
+    pub set_lookat_offset_using_input_behavior:
+        SetLookatOffsetUsingInputBehaviorComponent,
+    pub set_cylinder_coordinate_for_desired_transform_translation_angle_using_input_behavior:
+        SetCylinderCoordinateForDesiredTransformTranslationUsingInputBehaviorComponent,
+    pub set_transform_translation_using_collision_behavior:
+        SetTransformTranslationUsingCollisionBehaviorComponent,
+    pub set_desired_transform_using_camera_zone_behavior:
+        SetDesiredTransformUsingCameraZoneBehaviorComponent,
+    pub set_desired_transform_translation_using_camera_rail_behavior:
+        SetDesiredTransformTranslationUsingCameraRailBehaviorComponent,
+    pub set_cylinder_coordinates_for_desired_transform_translation_using_auto_follow_behavior:
+        SetCylinderCoordinatesForDesiredTransformTranslationUsingAutoFollowBehaviorComponent,
 }
 
 // REGION variables component
@@ -119,33 +181,185 @@ pub struct CylinderCoordinatesForDesiredTransformTranslationVariablesComponent
         CylindricalCoordinates,
 }
 
+/// component with input from player for camera.
+/// ie, amounts to change camera variables by this update.
+#[derive(Component)]
+pub struct CameraPlayerInputComponent {
+    /// change of cylinder rotation.
+    pub rotation_player_input: f32,
+
+    /// change of cylinder height.
+    pub height_player_input: f32,
+
+    /// change of cylinder distance.
+    pub distance_player_input: f32,
+
+    /// change of height of [LookatOffsetVariablesComponent].
+    pub lookat_offset_height_player_input:
+        f32,
+}
+
+/// parameters for smoothing a transition towards a desired value.
+#[derive(Clone, Copy)]
+pub struct SmoothDampParameters {
+    /// approximate time to reach the desired value, in seconds.
+    pub smooth_time: f32,
+
+    /// maximum speed of the transition, per second.
+    pub maximum_speed: f32,
+}
+
+/// component with parameters for the transitions of a camera.
+/// ie, how the camera transitions to its desired state.
+#[derive(Component)]
+pub struct CameraTransitionParametersComponent
+{
+    pub translation: SmoothDampParameters,
+    pub rotation: SmoothDampParameters,
+    pub parent_translation:
+        SmoothDampParameters,
+    pub parent_rotation:
+        SmoothDampParameters,
+    pub lookat_position:
+        SmoothDampParameters,
+    pub lookat_up: SmoothDampParameters,
+}
+
+/// component with velocities for the transitions of a camera.
+/// ie, state of smooth damp between updates.
+/// nb, rotation velocities are per quaternion component, so start at zero rather than identity.
+#[derive(Component)]
+pub struct CameraTransitionVariablesComponent
+{
+    pub translation_velocity: Vec3,
+    pub rotation_velocity: Quat,
+    pub parent_translation_velocity:
+        Vec3,
+    pub parent_rotation_velocity: Quat,
+    pub lookat_position_velocity: Vec3,
+    pub lookat_up_velocity: Vec3,
+}
+
+/// component with parameters for the transitions of a cylinder camera.
+#[derive(Component)]
+pub struct CylinderTransitionParametersComponent
+{
+    /// maximum distance, per axis, of [ParentTransformVariablesComponent] from [DesiredTransformParentVariablesComponent].
+    /// in the local frame of the desired parent transform.
+    /// ie, how far the cylinder origin may lag behind.
+    pub maximum_parent_translation_lag:
+        Vec3,
+}
+
+/// how a camera avoids the stage between it and its lookat position.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum CameraCollisionMode {
+    /// pull the camera in towards the lookat position.
+    PullIn,
+
+    /// hide the occluding stage, instead of moving the camera.
+    /// nb, hidden only for this camera using [RenderLayers], so occluders must not have render layers of their own.
+    HideOccluders,
+}
+
+/// component with parameters for avoiding the stage with a camera.
+#[derive(Component)]
+pub struct CameraCollisionParametersComponent
+{
+    pub mode: CameraCollisionMode,
+
+    /// render layer only this camera renders, besides the default layer.
+    /// ie, occluders hidden by this camera are removed from it.
+    pub render_layer: Layer,
+
+    /// radius of the sphere cast from the lookat position to the camera.
+    /// ie, how far the camera stays from the stage.
+    pub radius: f32,
+
+    /// transition of the camera distance back out, once the obstruction clears.
+    pub ease_out: SmoothDampParameters,
+}
+
+/// component with variables for avoiding the stage with a camera.
+#[derive(Component)]
+pub struct CameraCollisionVariablesComponent
+{
+    /// allowed distance of the camera from the lookat position.
+    pub distance: f32,
+    pub distance_velocity: f32,
+
+    /// occluders hidden by this camera.
+    pub hidden_entities: Vec<Entity>,
+
+    /// translation of the camera before it was pulled in.
+    /// ie, the smoothed translation the transition continues from, instead of the pulled in translation.
+    pub unobstructed_translation: Option<Vec3>,
+}
+
+/// component with parameters for swinging a cylinder camera behind the travel direction of the observed entity.
+#[derive(Component)]
+pub struct CameraAutoFollowParametersComponent
+{
+    /// time without camera input before following, in seconds.
+    pub idle_time: f32,
+
+    /// horizontal speed of the observed entity below which the camera does not follow, in m/s.
+    /// ie, a dead zone for small movements.
+    pub minimum_speed: f32,
+
+    /// horizontal speed of the observed entity at which the camera follows at full strength, in m/s.
+    pub full_strength_speed: f32,
+
+    /// transition of the cylinder rotation at full strength.
+    /// nb, weaker follows transition slower.
+    pub rotation: SmoothDampParameters,
+}
+
+/// component with variables for swinging a cylinder camera behind the travel direction of the observed entity.
+#[derive(Component, Default)]
+pub struct CameraAutoFollowVariablesComponent
+{
+    /// time since the last camera input, in seconds.
+    pub idle_time: f32,
+
+    pub rotation_velocity: f32,
+}
+
 // REGIONEND
 
 // REGION behavior component
 
 /// component for [set_desired_transform_translation_to_observed_entiy_transform_translation_behavior_system].
-#[derive(Component)]
+#[derive(Component, Default)]
 pub struct SetDesiredTransformTranslationToObservedEntityTransformTranslationBehaviorComponent;
 
 /// component for [set_desired_transform_rotation_to_observed_entity_local_up_behavior_system].
-#[derive(Component)]
+#[derive(Component, Default)]
 pub struct SetDesiredTransformRotationToObservedEntityLocalUpBehaviorComponent;
 
 /// component for [set_lookat_position_to_observed_entity_transform_translation_with_offset_behavior_system].
-#[derive(Component)]
+#[derive(Component, Default)]
 pub struct SetDesiredLookatPositionToObservedEntityTransformTranslationWithOffsetBehaviorComponent;
 
-#[derive(Component)]
+#[derive(Component, Default)]
 pub struct SetDesiredLookatUpToObservedEntityTransformLocalUpWithOffsetBehaviorComponent;
 
 /// behavior component to update [LookatOffsetVariablesComponent] using input.
-#[derive(Component)]
+#[derive(Component, Default)]
 pub struct SetLookatOffsetUsingInputBehaviorComponent;
 
 /// component for [set_cylinder_coordinates_for_desired_transform_translation_using_input_system].
-#[derive(Component)]
+#[derive(Component, Default)]
 pub struct SetCylinderCoordinateForDesiredTransformTranslationUsingInputBehaviorComponent;
 
+/// component for [set_transform_translation_using_collision_behavior_system].
+#[derive(Component, Default)]
+pub struct SetTransformTranslationUsingCollisionBehaviorComponent;
+
+/// component for [set_cylinder_coordinates_for_desired_transform_translation_using_auto_follow_behavior_system].
+#[derive(Component, Default)]
+pub struct SetCylinderCoordinatesForDesiredTransformTranslationUsingAutoFollowBehaviorComponent;
+
 // REGIONEND
 
 // REGION transition system
@@ -153,104 +367,267 @@ pub struct SetCylinderCoordinateForDesiredTransformTranslationUsingInputBehavior
 /// transition [Transform] using [DesiredTransformVariablesComponent].
 pub fn transition_transform_to_desired_transform_system(
     time: Res<Time>,
-    mut query: Query<(&mut Transform, &DesiredTransformVariablesComponent)>,
+    mut query: Query<(
+        &mut Transform,
+        &DesiredTransformVariablesComponent,
+        &CameraTransitionParametersComponent,
+        &mut CameraTransitionVariablesComponent,
+    )>,
 ) {
+    let delta_seconds =
+        time.delta_seconds();
+
+    if delta_seconds <= 0.0 {
+        return;
+    }
+
     for (
         mut transform,
         desired_transform_variables,
+        transition_parameters,
+        mut transition_variables,
     ) in query.iter_mut()
     {
-        let next_position = Vec3::lerp(
+        let (next_position, next_velocity) = Vec3::smooth_damp(
             transform.translation,
             desired_transform_variables
                 .desired_transform
                 .translation,
-            time.delta().as_secs_f32()
-                * 3.33,
+            transition_variables
+                .translation_velocity,
+            transition_parameters
+                .translation
+                .smooth_time,
+            transition_parameters
+                .translation
+                .maximum_speed,
+            delta_seconds,
         );
-        let next_rotation = Quat::slerp(
+        let (next_rotation, next_angular_velocity) = Quat::smooth_damp(
             transform.rotation,
             desired_transform_variables
                 .desired_transform
                 .rotation,
-            time.delta().as_secs_f32()
-                * 3.33,
+            transition_variables
+                .rotation_velocity,
+            transition_parameters
+                .rotation
+                .smooth_time,
+            transition_parameters
+                .rotation
+                .maximum_speed,
+            delta_seconds,
         );
 
         transform.translation =
             next_position;
         transform.rotation =
             next_rotation;
+        transition_variables
+            .translation_velocity =
+            next_velocity;
+        transition_variables
+            .rotation_velocity =
+            next_angular_velocity;
     }
 }
 
+/// transition [LookatVariablesComponent] using [DesiredLookatVariablesComponent].
 pub fn transition_lookat_variables_to_desired_lookat_variables_system(
     time: Res<Time>,
-    mut query: Query<(&mut LookatVariablesComponent, &DesiredLookatVariablesComponent)>,
+    mut query: Query<(
+        &mut LookatVariablesComponent,
+        &DesiredLookatVariablesComponent,
+        &CameraTransitionParametersComponent,
+        &mut CameraTransitionVariablesComponent,
+    )>,
 ) {
+    let delta_seconds =
+        time.delta_seconds();
+
+    if delta_seconds <= 0.0 {
+        return;
+    }
+
     for (
         mut lookat_variables,
         desired_lookat_variables,
+        transition_parameters,
+        mut transition_variables,
     ) in query.iter_mut()
     {
-        let next_position = Vec3::lerp(
+        let (next_position, next_velocity) = Vec3::smooth_damp(
             lookat_variables.position,
             desired_lookat_variables
                 .position,
-            time.delta().as_secs_f32()
-                * 3.33,
+            transition_variables
+                .lookat_position_velocity,
+            transition_parameters
+                .lookat_position
+                .smooth_time,
+            transition_parameters
+                .lookat_position
+                .maximum_speed,
+            delta_seconds,
         );
-        let next_up = Vec3::slerp(
-            &lookat_variables.up,
+        let (next_up, next_up_velocity) = Vec3::smooth_damp(
+            lookat_variables.up,
             desired_lookat_variables.up,
-            time.delta().as_secs_f32()
-                * 3.33,
+            transition_variables
+                .lookat_up_velocity,
+            transition_parameters
+                .lookat_up
+                .smooth_time,
+            transition_parameters
+                .lookat_up
+                .maximum_speed,
+            delta_seconds,
         );
 
         lookat_variables.position =
             next_position;
-        lookat_variables.up = next_up;
+        lookat_variables.up = next_up
+            .try_normalize()
+            .unwrap_or(
+                desired_lookat_variables
+                    .up,
+            );
+        transition_variables
+            .lookat_position_velocity =
+            next_velocity;
+        transition_variables
+            .lookat_up_velocity =
+            next_up_velocity;
     }
 }
 
 /// transition [ParentTransformVariablesComponent] using [DesiredTransformParentVariablesComponent].
 pub fn transition_parent_transform_to_desired_parent_transform_system(
     time: Res<Time>,
-    mut query: Query<(&mut ParentTransformVariablesComponent, &DesiredTransformParentVariablesComponent)>,
+    mut query: Query<(
+        &mut ParentTransformVariablesComponent,
+        &DesiredTransformParentVariablesComponent,
+        &CameraTransitionParametersComponent,
+        &mut CameraTransitionVariablesComponent,
+        &CylinderTransitionParametersComponent,
+    )>,
 ) {
+    let delta_seconds =
+        time.delta_seconds();
+
+    if delta_seconds <= 0.0 {
+        return;
+    }
+
     for (
         mut parent_transform,
         desired_parent_transform,
+        transition_parameters,
+        mut transition_variables,
+        cylinder_transition_parameters,
     ) in query.iter_mut()
     {
-        let next_position = Vec3::lerp(
+        let (next_position, next_velocity) = Vec3::smooth_damp(
             parent_transform
                 .transform
                 .translation,
             desired_parent_transform
                 .transform
                 .translation,
-            time.delta().as_secs_f32()
-                * 3.33,
+            transition_variables
+                .parent_translation_velocity,
+            transition_parameters
+                .parent_translation
+                .smooth_time,
+            transition_parameters
+                .parent_translation
+                .maximum_speed,
+            delta_seconds,
         );
-        let next_rotation = Quat::slerp(
+        let (next_rotation, next_angular_velocity) = Quat::smooth_damp(
             parent_transform
                 .transform
                 .rotation,
             desired_parent_transform
                 .transform
                 .rotation,
-            time.delta().as_secs_f32()
-                * 3.33,
+            transition_variables
+                .parent_rotation_velocity,
+            transition_parameters
+                .parent_rotation
+                .smooth_time,
+            transition_parameters
+                .parent_rotation
+                .maximum_speed,
+            delta_seconds,
+        );
+
+        // clamp lag behind desired parent transform, per local axis
+        let local_lag = Quat::mul_vec3(
+            desired_parent_transform
+                .transform
+                .rotation
+                .inverse(),
+            next_position
+                - desired_parent_transform
+                    .transform
+                    .translation,
+        );
+        let clamped_local_lag = Vec3::clamp(
+            local_lag,
+            -cylinder_transition_parameters
+                .maximum_parent_translation_lag,
+            cylinder_transition_parameters
+                .maximum_parent_translation_lag,
+        );
+        let clamped_next_position = desired_parent_transform
+            .transform
+            .translation
+            + Quat::mul_vec3(
+                desired_parent_transform
+                    .transform
+                    .rotation,
+                clamped_local_lag,
+            );
+
+        // stop moving along clamped axes, instead of pushing against the clamp
+        let mut local_next_velocity = Quat::mul_vec3(
+            desired_parent_transform
+                .transform
+                .rotation
+                .inverse(),
+            next_velocity,
         );
+        if clamped_local_lag.x != local_lag.x {
+            local_next_velocity.x = 0.0;
+        }
+
+        if clamped_local_lag.y != local_lag.y {
+            local_next_velocity.y = 0.0;
+        }
+
+        if clamped_local_lag.z != local_lag.z {
+            local_next_velocity.z = 0.0;
+        }
 
         parent_transform
             .transform
             .translation =
-            next_position;
+            clamped_next_position;
         parent_transform
             .transform
             .rotation = next_rotation;
+        transition_variables
+            .parent_translation_velocity =
+            Quat::mul_vec3(
+                desired_parent_transform
+                    .transform
+                    .rotation,
+                local_next_velocity,
+            );
+        transition_variables
+            .parent_rotation_velocity =
+            next_angular_velocity;
     }
 }
 
@@ -426,76 +803,620 @@ pub fn set_desired_lookat_up_to_observed_entity_transform_local_up_with_offset_b
 }
 
 pub fn set_cylinder_coordinates_for_desired_transform_translation_using_input_system(
-    mut mouse_motion_events: EventReader<MouseMotion>,
-    mut mouse_wheel_events: EventReader<
-        MouseWheel,
-    >,
     mut query: Query<
-        (&mut CylinderCoordinatesForDesiredTransformTranslationVariablesComponent,),
+        (&mut CylinderCoordinatesForDesiredTransformTranslationVariablesComponent, &CameraPlayerInputComponent),
         With<SetCylinderCoordinateForDesiredTransformTranslationUsingInputBehaviorComponent>,
     >,
 ) {
-    let mut input = Vec2::ZERO;
-    for mouse_event in
-        mouse_motion_events.read()
-    {
-        input.x +=
-            mouse_event.delta.x * 0.001;
-        input.y +=
-            mouse_event.delta.y * 0.001;
-    }
-
-    let mut zoom_input: f32 = 0.0;
-    for mouse_event in
-        mouse_wheel_events.read()
-    {
-        zoom_input +=
-            mouse_event.y * 0.1;
-    }
-
-    for mut
-    desired_cylinder_coordinates_for_transform in
-        query.iter_mut()
+    for (
+        mut desired_cylinder_coordinates_for_transform,
+        player_input,
+    ) in query.iter_mut()
     {
         desired_cylinder_coordinates_for_transform
-            .0
             .cylinder_coordindates
-            .distance -= zoom_input;
+            .distance += player_input
+            .distance_player_input;
 
         desired_cylinder_coordinates_for_transform
-            .0
             .cylinder_coordindates
-            .rotation += input.x;
+            .rotation += player_input
+            .rotation_player_input;
         desired_cylinder_coordinates_for_transform
-            .0
             .cylinder_coordindates
-            .height -= input.y * 0.5;
+            .height += player_input
+            .height_player_input;
     }
 }
 
 /// set [LookatOffsetVariablesComponent] on [SetLookatOffsetUsingInputBehaviorComponent].
 pub fn set_lookat_offset_using_input_system(
-    mut mouse_motion_events: EventReader<MouseMotion>,
     mut query: Query<
-        (&mut LookatOffsetVariablesComponent,),
+        (
+            &mut LookatOffsetVariablesComponent,
+            &CameraPlayerInputComponent,
+        ),
         With<SetLookatOffsetUsingInputBehaviorComponent>,
     >,
 ) {
-    let mut input = Vec2::ZERO;
-    for mouse_event in
-        mouse_motion_events.read()
+    for (
+        mut lookat_offset_variables,
+        player_input,
+    ) in query.iter_mut()
     {
-        input.y +=
-            mouse_event.delta.y * 0.001;
+        lookat_offset_variables
+            .translation_wrt_observed
+            .y += player_input
+            .lookat_offset_height_player_input;
     }
+}
 
-    for mut lookat_offset_variables in
-        query.iter_mut()
+/// set [CylinderCoordinatesForDesiredTransformTranslationVariablesComponent] rotation behind the horizontal velocity of [ObservedEntityVariablesComponent].
+/// ie, after [CameraAutoFollowParametersComponent] idle time without camera input, swing the camera behind the travel direction, stronger when faster.
+pub fn set_cylinder_coordinates_for_desired_transform_translation_using_auto_follow_behavior_system(
+    time: Res<Time>,
+    mut query: Query<
+        (
+            &ObservedEntityVariablesComponent,
+            &ParentTransformVariablesComponent,
+            &CameraPlayerInputComponent,
+            &CameraAutoFollowParametersComponent,
+            &mut CameraAutoFollowVariablesComponent,
+            &mut CylinderCoordinatesForDesiredTransformTranslationVariablesComponent,
+        ),
+        With<SetCylinderCoordinatesForDesiredTransformTranslationUsingAutoFollowBehaviorComponent>,
+    >,
+    observed_query: Query<&Velocity>,
+) {
+    let delta_seconds =
+        time.delta_seconds();
+
+    if delta_seconds <= 0.0 {
+        return;
+    }
+
+    for (
+        observed_entity,
+        parent_transform,
+        player_input,
+        auto_follow_parameters,
+        mut auto_follow_variables,
+        mut cylinder_coordinates_for_desired_transform,
+    ) in query.iter_mut()
     {
-        lookat_offset_variables
-            .0
-            .translation_wrt_observed
-            .y += input.y * 0.2
+        if player_input.rotation_player_input
+            != 0.0
+            || player_input
+                .height_player_input
+                != 0.0
+            || player_input
+                .distance_player_input
+                != 0.0
+        {
+            auto_follow_variables.idle_time =
+                0.0;
+            auto_follow_variables
+                .rotation_velocity = 0.0;
+
+            continue;
+        }
+
+        auto_follow_variables.idle_time +=
+            delta_seconds;
+
+        if auto_follow_variables.idle_time
+            < auto_follow_parameters.idle_time
+        {
+            continue;
+        }
+
+        let observed_velocity_result =
+            observed_query
+                .get(observed_entity.entity);
+
+        if observed_velocity_result.is_err() {
+            continue;
+        }
+
+        // in the frame of the cylinder
+        let local_velocity = Quat::mul_vec3(
+            parent_transform
+                .transform
+                .rotation
+                .inverse(),
+            observed_velocity_result
+                .unwrap()
+                .linvel,
+        );
+        let horizontal_speed = f32::sqrt(
+            local_velocity.x
+                * local_velocity.x
+                + local_velocity.z
+                    * local_velocity.z,
+        );
+
+        let strength = f32::clamp(
+            (horizontal_speed
+                - auto_follow_parameters
+                    .minimum_speed)
+                / f32::max(
+                    auto_follow_parameters
+                        .full_strength_speed
+                        - auto_follow_parameters
+                            .minimum_speed,
+                    f32::EPSILON,
+                ),
+            0.0,
+            1.0,
+        );
+
+        if strength <= 0.0 {
+            auto_follow_variables
+                .rotation_velocity = 0.0;
+
+            continue;
+        }
+
+        // behind, ie, opposite of the velocity
+        let rotation = cylinder_coordinates_for_desired_transform
+            .cylinder_coordindates
+            .rotation;
+        let behind_rotation = f32::atan2(
+            -local_velocity.z,
+            -local_velocity.x,
+        );
+        let rotation_difference = (behind_rotation
+            - rotation
+            + PI)
+            .rem_euclid(TAU)
+            - PI;
+
+        let (
+            next_rotation,
+            next_rotation_velocity,
+        ) = f32::smooth_damp(
+            rotation,
+            rotation + rotation_difference,
+            auto_follow_variables
+                .rotation_velocity,
+            auto_follow_parameters
+                .rotation
+                .smooth_time
+                / strength,
+            auto_follow_parameters
+                .rotation
+                .maximum_speed
+                * strength,
+            delta_seconds,
+        );
+
+        cylinder_coordinates_for_desired_transform
+            .cylinder_coordindates
+            .rotation = next_rotation;
+        auto_follow_variables
+            .rotation_velocity =
+            next_rotation_velocity;
+    }
+}
+
+/// set [Transform] translation so the stage is not between the camera and [LookatVariablesComponent].
+/// ie, pull the camera in on hit, and ease it back out when the obstruction clears.
+/// or, hide the occluders, using [CameraCollisionMode::HideOccluders].
+pub fn set_transform_translation_using_collision_behavior_system(
+    time: Res<Time>,
+    rapier_context: Res<RapierContext>,
+    mut commands: Commands,
+    mut query: Query<
+        (
+            &mut Transform,
+            &LookatVariablesComponent,
+            &CameraCollisionParametersComponent,
+            &mut CameraCollisionVariablesComponent,
+        ),
+        With<SetTransformTranslationUsingCollisionBehaviorComponent>,
+    >,
+    render_layers_query: Query<
+        &RenderLayers,
+    >,
+    children_query: Query<&Children>,
+) {
+    let delta_seconds =
+        time.delta_seconds();
+
+    if delta_seconds <= 0.0 {
+        return;
+    }
+
+    let stage_query_filter =
+        QueryFilter::new().groups(
+            CollisionGroups::new(
+                Group::from_bits(0b0100)
+                    .unwrap(),
+                Group::from_bits(0b0010)
+                    .unwrap(),
+            ),
+        );
+
+    let mut occluder_render_layers: HashMap<
+        Entity,
+        RenderLayers,
+    > = HashMap::new();
+
+    for (
+        mut transform,
+        lookat_variables,
+        collision_parameters,
+        mut collision_variables,
+    ) in query.iter_mut()
+    {
+        collision_variables
+            .unobstructed_translation =
+            Some(transform.translation);
+
+        let lookat_to_camera = transform
+            .translation
+            - lookat_variables.position;
+        let unobstructed_distance =
+            lookat_to_camera.length();
+
+        if unobstructed_distance <= 0.0 {
+            continue;
+        }
+
+        let direction = lookat_to_camera
+            / unobstructed_distance;
+
+        // occluders, from the lookat position to the camera
+        let mut occluder_entities: Vec<
+            Entity,
+        > = Vec::new();
+        if collision_parameters.mode
+            == CameraCollisionMode::HideOccluders
+        {
+            rapier_context
+                .intersections_with_ray(
+                    lookat_variables.position,
+                    direction,
+                    unobstructed_distance,
+                    true,
+                    stage_query_filter,
+                    |occluder_entity, _| {
+                        occluder_entities
+                            .push(
+                                occluder_entity,
+                            );
+                        return true;
+                    },
+                );
+        }
+
+        // show occluders which no longer occlude
+        for hidden_entity in
+            collision_variables
+                .hidden_entities
+                .iter()
+        {
+            if occluder_entities
+                .contains(hidden_entity)
+            {
+                continue;
+            }
+
+            set_occluder_hidden_for_render_layer(
+                &mut occluder_render_layers,
+                &render_layers_query,
+                *hidden_entity,
+                collision_parameters
+                    .render_layer,
+                false,
+            );
+        }
+
+        for occluder_entity in
+            occluder_entities.iter()
+        {
+            set_occluder_hidden_for_render_layer(
+                &mut occluder_render_layers,
+                &render_layers_query,
+                *occluder_entity,
+                collision_parameters
+                    .render_layer,
+                true,
+            );
+        }
+
+        collision_variables
+            .hidden_entities =
+            occluder_entities;
+
+        if collision_parameters.mode
+            == CameraCollisionMode::HideOccluders
+        {
+            collision_variables.distance =
+                unobstructed_distance;
+            collision_variables
+                .distance_velocity = 0.0;
+
+            continue;
+        }
+
+        let hit_distance = rapier_context
+            .cast_shape(
+                lookat_variables.position,
+                Quat::IDENTITY,
+                direction,
+                &Collider::ball(
+                    collision_parameters
+                        .radius,
+                ),
+                unobstructed_distance,
+                true,
+                stage_query_filter,
+            )
+            .map(|(_, hit)| hit.toi)
+            .unwrap_or(
+                unobstructed_distance,
+            );
+
+        if hit_distance
+            < collision_variables.distance
+        {
+            // pull in immediately
+            collision_variables.distance =
+                hit_distance;
+            collision_variables
+                .distance_velocity = 0.0;
+        } else {
+            let (
+                next_distance,
+                next_distance_velocity,
+            ) = f32::smooth_damp(
+                collision_variables
+                    .distance,
+                hit_distance,
+                collision_variables
+                    .distance_velocity,
+                collision_parameters
+                    .ease_out
+                    .smooth_time,
+                collision_parameters
+                    .ease_out
+                    .maximum_speed,
+                delta_seconds,
+            );
+
+            collision_variables.distance =
+                next_distance;
+            collision_variables
+                .distance_velocity =
+                next_distance_velocity;
+        }
+
+        transform.translation =
+            lookat_variables.position
+                + direction
+                    * f32::min(
+                        collision_variables
+                            .distance,
+                        unobstructed_distance,
+                    );
+    }
+
+    apply_occluder_render_layers(
+        &mut commands,
+        occluder_render_layers,
+        &render_layers_query,
+        &children_query,
+    );
+}
+
+/// restore [Transform] translation to before [set_transform_translation_using_collision_behavior_system] pulled it in.
+/// ie, the transition smooths from the unobstructed translation, so its velocity does not build up while the camera is pulled in.
+pub fn restore_transform_translation_before_collision_behavior_system(
+    mut query: Query<
+        (
+            &mut Transform,
+            &mut CameraCollisionVariablesComponent,
+        ),
+        With<SetTransformTranslationUsingCollisionBehaviorComponent>,
+    >,
+) {
+    for (
+        mut transform,
+        mut collision_variables,
+    ) in query.iter_mut()
+    {
+        if collision_variables
+            .unobstructed_translation
+            .is_none()
+        {
+            continue;
+        }
+
+        transform.translation =
+            collision_variables
+                .unobstructed_translation
+                .unwrap();
+        collision_variables
+            .unobstructed_translation = None;
+    }
+}
+
+/// show the occluders hidden by cameras without [SetTransformTranslationUsingCollisionBehaviorComponent].
+/// ie, switching camera modes does not leave the stage hidden.
+pub fn show_occluders_hidden_without_collision_behavior_system(
+    mut commands: Commands,
+    mut query: Query<
+        (
+            &CameraCollisionParametersComponent,
+            &mut CameraCollisionVariablesComponent,
+        ),
+        Without<SetTransformTranslationUsingCollisionBehaviorComponent>,
+    >,
+    render_layers_query: Query<
+        &RenderLayers,
+    >,
+    children_query: Query<&Children>,
+) {
+    let mut occluder_render_layers: HashMap<
+        Entity,
+        RenderLayers,
+    > = HashMap::new();
+
+    for (
+        collision_parameters,
+        mut collision_variables,
+    ) in query.iter_mut()
+    {
+        // the camera is no longer pulled in
+        collision_variables
+            .unobstructed_translation = None;
+
+        if collision_variables
+            .hidden_entities
+            .is_empty()
+        {
+            continue;
+        }
+
+        for hidden_entity in
+            collision_variables
+                .hidden_entities
+                .iter()
+        {
+            set_occluder_hidden_for_render_layer(
+                &mut occluder_render_layers,
+                &render_layers_query,
+                *hidden_entity,
+                collision_parameters
+                    .render_layer,
+                false,
+            );
+        }
+
+        collision_variables
+            .hidden_entities
+            .clear();
+    }
+
+    apply_occluder_render_layers(
+        &mut commands,
+        occluder_render_layers,
+        &render_layers_query,
+        &children_query,
+    );
+}
+
+/// render layers of occluders visible to all cameras.
+/// ie, every layer but the default, so each camera renders them through its own render layer.
+const VISIBLE_OCCLUDER_RENDER_LAYERS: RenderLayers =
+    RenderLayers::all().without(0);
+
+/// hide or show an occluder for the camera using a render layer.
+/// ie, an occluder hidden from a camera is removed from the render layer of that camera.
+fn set_occluder_hidden_for_render_layer(
+    occluder_render_layers: &mut HashMap<
+        Entity,
+        RenderLayers,
+    >,
+    render_layers_query: &Query<
+        &RenderLayers,
+    >,
+    occluder_entity: Entity,
+    render_layer: Layer,
+    is_hidden: bool,
+) {
+    // without render layers, visible to all cameras
+    let render_layers =
+        occluder_render_layers
+            .get(&occluder_entity)
+            .copied()
+            .or_else(|| {
+                render_layers_query
+                    .get(occluder_entity)
+                    .ok()
+                    .copied()
+            })
+            .unwrap_or(
+                VISIBLE_OCCLUDER_RENDER_LAYERS,
+            );
+
+    occluder_render_layers.insert(
+        occluder_entity,
+        if is_hidden {
+            render_layers
+                .without(render_layer)
+        } else {
+            render_layers.with(render_layer)
+        },
+    );
+}
+
+/// apply render layers to occluders and their descendants.
+/// ie, occluders visible to all cameras have their render layers removed.
+fn apply_occluder_render_layers(
+    commands: &mut Commands,
+    occluder_render_layers: HashMap<
+        Entity,
+        RenderLayers,
+    >,
+    render_layers_query: &Query<
+        &RenderLayers,
+    >,
+    children_query: &Query<&Children>,
+) {
+    for (
+        occluder_entity,
+        render_layers,
+    ) in occluder_render_layers
+    {
+        let current_render_layers =
+            render_layers_query
+                .get(occluder_entity)
+                .ok()
+                .copied()
+                .unwrap_or(
+                    VISIBLE_OCCLUDER_RENDER_LAYERS,
+                );
+
+        if current_render_layers
+            == render_layers
+        {
+            continue;
+        }
+
+        for entity in
+            std::iter::once(occluder_entity)
+                .chain(
+                    children_query
+                        .iter_descendants(
+                            occluder_entity,
+                        ),
+                )
+        {
+            let entity_commands_result =
+                commands.get_entity(entity);
+
+            if entity_commands_result
+                .is_none()
+            {
+                continue;
+            }
+
+            let mut entity_commands =
+                entity_commands_result
+                    .unwrap();
+
+            if render_layers
+                == VISIBLE_OCCLUDER_RENDER_LAYERS
+            {
+                entity_commands
+                    .remove::<RenderLayers>();
+            } else {
+                entity_commands
+                    .insert(render_layers);
+            }
+        }
     }
 }
 
@@ -503,6 +1424,47 @@ pub fn set_lookat_offset_using_input_system(
 
 // REGION
 
+/// draw the bounds [CylinderTransitionParametersComponent] clamps the cylinder origin to.
+pub fn draw_camera_parent_translation_lag_gizmos(
+    mut gizmos: Gizmos,
+    query: Query<(
+        &ParentTransformVariablesComponent,
+        &DesiredTransformParentVariablesComponent,
+        &CylinderTransitionParametersComponent,
+    )>,
+) {
+    for (
+        parent_transform,
+        desired_parent_transform,
+        cylinder_transition_parameters,
+    ) in query.iter()
+    {
+        gizmos.cuboid(
+            Transform {
+                translation: desired_parent_transform
+                    .transform
+                    .translation,
+                rotation: desired_parent_transform
+                    .transform
+                    .rotation,
+                scale: cylinder_transition_parameters
+                    .maximum_parent_translation_lag
+                    * 2.0,
+            },
+            Color::ORANGE,
+        );
+        gizmos.line(
+            desired_parent_transform
+                .transform
+                .translation,
+            parent_transform
+                .transform
+                .translation,
+            Color::ORANGE,
+        );
+    }
+}
+
 pub fn draw_camera_lookat_gizmos(
     mut gizmos: Gizmos,
     query: Query<(
@@ -0,0 +1,170 @@
+use bevy::{
+    ecs::{
+        component::Component,
+        query::With,
+        system::{Query, Res},
+    },
+    math::{EulerRot, Quat, Vec3},
+    time::Time,
+    transform::components::Transform,
+};
+use std::f32::consts::FRAC_PI_2;
+
+use crate::math::CylindricalCoordinates;
+
+/// component with parameters for "fly camera".
+/// ie, a free camera for debugging, which is not attached to a character.
+#[derive(Component)]
+pub struct FlyCameraParametersComponent
+{
+    /// speed while flying, in m/s.
+    pub speed: f32,
+
+    /// scale of speed while flying fast.
+    pub fast_speed_scale: f32,
+
+    /// scale of speed while flying slow.
+    pub slow_speed_scale: f32,
+
+    /// maximum pitch up or down, in radians.
+    pub maximum_pitch: f32,
+}
+
+/// component with variables for "fly camera".
+#[derive(Component)]
+pub struct FlyCameraVariablesComponent
+{
+    pub translation: Vec3,
+
+    /// rotation about global up, in radians.
+    pub yaw: f32,
+
+    /// rotation about local right, in radians, positive is upwards.
+    pub pitch: f32,
+
+    /// cylinder coordinates of the actor camera when it started flying.
+    /// ie, restored when it stops flying.
+    pub stored_cylinder_coordinates:
+        CylindricalCoordinates,
+}
+
+/// component with input from player for "fly camera".
+/// ie, amounts to change fly camera variables by this update.
+#[derive(Component)]
+pub struct FlyCameraPlayerInputComponent
+{
+    /// movement in the local frame of the camera.
+    /// ie, x is right, y is global up, z is backwards.
+    pub movement_player_input: Vec3,
+
+    /// change of yaw.
+    pub yaw_player_input: f32,
+
+    /// change of pitch.
+    pub pitch_player_input: f32,
+
+    pub is_fast_player_input: bool,
+    pub is_slow_player_input: bool,
+}
+
+// REGION behavior component
+
+/// component for [set_transform_using_fly_input_behavior_system].
+#[derive(Component)]
+pub struct SetTransformUsingFlyInputBehaviorComponent;
+
+// REGIONEND
+
+// REGION behavior system
+
+/// set [Transform] using [FlyCameraPlayerInputComponent].
+/// ie, move along the camera's forward and right, and turn with yaw and pitch.
+pub fn set_transform_using_fly_input_behavior_system(
+    time: Res<Time>,
+    mut query: Query<
+        (
+            &mut Transform,
+            &FlyCameraParametersComponent,
+            &mut FlyCameraVariablesComponent,
+            &FlyCameraPlayerInputComponent,
+        ),
+        With<SetTransformUsingFlyInputBehaviorComponent>,
+    >,
+) {
+    for (
+        mut transform,
+        fly_parameters,
+        mut fly_variables,
+        fly_player_input,
+    ) in query.iter_mut()
+    {
+        fly_variables.yaw -=
+            fly_player_input
+                .yaw_player_input;
+        fly_variables.pitch = f32::clamp(
+            fly_variables.pitch
+                - fly_player_input
+                    .pitch_player_input,
+            -f32::min(
+                fly_parameters
+                    .maximum_pitch,
+                FRAC_PI_2,
+            ),
+            f32::min(
+                fly_parameters
+                    .maximum_pitch,
+                FRAC_PI_2,
+            ),
+        );
+
+        let rotation = Quat::from_euler(
+            EulerRot::YXZ,
+            fly_variables.yaw,
+            fly_variables.pitch,
+            0.0,
+        );
+
+        let mut speed =
+            fly_parameters.speed;
+        if fly_player_input
+            .is_fast_player_input
+        {
+            speed *= fly_parameters
+                .fast_speed_scale;
+        }
+
+        if fly_player_input
+            .is_slow_player_input
+        {
+            speed *= fly_parameters
+                .slow_speed_scale;
+        }
+
+        let movement = Quat::mul_vec3(
+            rotation,
+            Vec3::new(
+                fly_player_input
+                    .movement_player_input
+                    .x,
+                0.0,
+                fly_player_input
+                    .movement_player_input
+                    .z,
+            ),
+        ) + Vec3::Y
+            * fly_player_input
+                .movement_player_input
+                .y;
+
+        fly_variables.translation +=
+            movement
+                * speed
+                * time.delta_seconds();
+
+        transform.translation =
+            fly_variables.translation;
+        transform.rotation = rotation;
+    }
+}
+
+// REGIONEND
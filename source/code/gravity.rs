@@ -0,0 +1,212 @@
+use bevy::{
+    ecs::{
+        component::Component,
+        query::{With, Without},
+        system::{Query, Res, Resource},
+    },
+    math::{cubic_splines::CubicCurve, Vec3},
+    transform::components::GlobalTransform,
+};
+use bevy_rapier3d::geometry::Collider;
+
+use crate::{
+    character::CharacterTagComponent,
+    math::find_nearest_parameter_on_curve,
+};
+
+/// number of samples for each segment of a spline field, when projecting onto it.
+const GRAVITY_SPLINE_SUBDIVISIONS_PER_SEGMENT: usize =
+    16;
+
+/// resource with the gravity of the world.
+/// ie, gravity outside of all gravity volumes.
+#[derive(Resource)]
+pub struct GravityResource {
+    /// direction of gravity.
+    pub direction: Vec3,
+}
+
+/// field of a gravity volume.
+#[derive(Clone)]
+pub enum GravityField {
+    /// gravity in a constant direction, in the volume's local space.
+    Uniform { direction: Vec3 },
+
+    /// gravity towards the origin of the volume.
+    /// ie, planetoids.
+    Point {
+        /// if gravity is away from the origin, instead of towards it.
+        is_outward: bool,
+    },
+
+    /// gravity towards the nearest point of a spline axis, in the volume's local space.
+    /// ie, loop tubes. a straight axis is a cylinder.
+    Spline {
+        curve: CubicCurve<Vec3>,
+
+        /// if gravity is away from the axis, instead of towards it.
+        /// ie, running on the inside of a tube.
+        is_outward: bool,
+    },
+}
+
+/// component for a zone entity with gravity inside of its collider.
+/// nb, the collider should be a sensor which does not collide with anything.
+#[derive(Component)]
+pub struct GravityVolumeComponent {
+    pub field: GravityField,
+
+    /// volumes with a higher priority override overlapping volumes with a lower priority.
+    pub priority: i32,
+}
+
+/// component with the gravity resolved for a character.
+#[derive(Component)]
+pub struct CharacterGravityVariablesComponent
+{
+    /// direction of gravity at the character.
+    pub direction: Vec3,
+}
+
+/// direction of gravity of a field, at a global position.
+/// zero when the direction is undefined, ie, at the origin of a point field.
+fn find_gravity_direction(
+    field: &GravityField,
+    volume_global_transform: &GlobalTransform,
+    position: Vec3,
+) -> Vec3 {
+    let local_position =
+        volume_global_transform
+            .affine()
+            .inverse()
+            .transform_point3(position);
+
+    let local_direction = match field {
+        GravityField::Uniform {
+            direction,
+        } => *direction,
+        GravityField::Point {
+            is_outward,
+        } => {
+            if *is_outward {
+                local_position
+            } else {
+                -local_position
+            }
+        }
+        GravityField::Spline {
+            curve,
+            is_outward,
+        } => {
+            let nearest_parameter_result =
+                find_nearest_parameter_on_curve(
+                    curve,
+                    local_position,
+                    GRAVITY_SPLINE_SUBDIVISIONS_PER_SEGMENT,
+                );
+
+            if nearest_parameter_result
+                .is_none()
+            {
+                return Vec3::ZERO;
+            }
+
+            let nearest_point = curve.position(
+                nearest_parameter_result
+                    .unwrap(),
+            );
+
+            if *is_outward {
+                local_position
+                    - nearest_point
+            } else {
+                nearest_point
+                    - local_position
+            }
+        }
+    };
+
+    return volume_global_transform
+        .affine()
+        .transform_vector3(local_direction)
+        .normalize_or_zero();
+}
+
+/// system to resolve gravity for each character from the gravity volume it is in, with the highest priority.
+/// ie, [GravityResource] when it is in no gravity volume.
+pub fn update_character_gravity_system(
+    gravity: Res<GravityResource>,
+    mut character_query: Query<
+        (
+            &GlobalTransform,
+            &mut CharacterGravityVariablesComponent,
+        ),
+        With<CharacterTagComponent>,
+    >,
+    gravity_volume_query: Query<
+        (
+            &GlobalTransform,
+            &Collider,
+            &GravityVolumeComponent,
+        ),
+        Without<CharacterTagComponent>,
+    >,
+) {
+    for mut character in
+        character_query.iter_mut()
+    {
+        let character_position =
+            character.0.translation();
+
+        let gravity_volume_result =
+            gravity_volume_query
+                .iter()
+                .filter(|gravity_volume| {
+                    let (
+                        _,
+                        volume_rotation,
+                        volume_translation,
+                    ) = gravity_volume
+                        .0
+                        .to_scale_rotation_translation();
+
+                    return gravity_volume
+                        .1
+                        .contains_point(
+                            volume_translation,
+                            volume_rotation,
+                            character_position,
+                        );
+                })
+                .max_by_key(|gravity_volume| {
+                    gravity_volume.2.priority
+                });
+
+        let mut gravity_direction =
+            gravity.direction.normalize_or_zero();
+
+        if let Some(gravity_volume) =
+            gravity_volume_result
+        {
+            let volume_gravity_direction =
+                find_gravity_direction(
+                    &gravity_volume.2.field,
+                    gravity_volume.0,
+                    character_position,
+                );
+
+            // keep the previous direction where the field is undefined
+            gravity_direction =
+                if volume_gravity_direction
+                    == Vec3::ZERO
+                {
+                    character.1.direction
+                } else {
+                    volume_gravity_direction
+                };
+        }
+
+        character.1.direction =
+            gravity_direction;
+    }
+}
@@ -0,0 +1,301 @@
+use bevy::{
+    asset::ron,
+    ecs::{
+        change_detection::DetectChanges,
+        system::{
+            Commands, Res, Resource,
+        },
+    },
+    input::{
+        gamepad::{
+            GamepadAxisType,
+            GamepadButtonType,
+        },
+        keyboard::KeyCode,
+    },
+    log::warn,
+};
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+use crate::player::PlayerGamepadParametersComponent;
+
+/// path of the file with input bindings, relative to the working directory.
+/// nb, this file is not tracked, so each developer can keep their own layout.
+pub const INPUT_BINDINGS_PATH: &str =
+    "input_bindings.ron";
+
+/// analog stick of a gamepad.
+#[derive(Serialize, Deserialize)]
+pub enum GamepadStick {
+    Left,
+    Right,
+}
+
+impl GamepadStick {
+    /// axes of this stick, horizontal then vertical.
+    pub fn axis_types(
+        &self,
+    ) -> (GamepadAxisType, GamepadAxisType)
+    {
+        return match self {
+            GamepadStick::Left => (
+                GamepadAxisType::LeftStickX,
+                GamepadAxisType::LeftStickY,
+            ),
+            GamepadStick::Right => (
+                GamepadAxisType::RightStickX,
+                GamepadAxisType::RightStickY,
+            ),
+        };
+    }
+}
+
+/// bindings for keyboard and mouse.
+#[derive(Serialize, Deserialize)]
+pub struct KeyboardAndMouseBindings {
+    pub move_forward_key: KeyCode,
+    pub move_back_key: KeyCode,
+    pub move_left_key: KeyCode,
+    pub move_right_key: KeyCode,
+    pub jump_key: KeyCode,
+
+    /// nb, defaults when missing, so bindings files saved before the fly camera still load.
+    #[serde(default)]
+    pub fly_camera: FlyCameraKeyBindings,
+
+    /// camera orbit per horizontal mouse motion.
+    pub camera_orbit_sensitivity: f32,
+
+    /// camera pitch per vertical mouse motion.
+    pub camera_pitch_sensitivity: f32,
+
+    /// camera zoom per mouse wheel motion.
+    pub camera_zoom_sensitivity: f32,
+}
+
+/// bindings for gamepads.
+#[derive(Serialize, Deserialize)]
+pub struct GamepadBindings {
+    pub move_stick: GamepadStick,
+    pub jump_button: GamepadButtonType,
+
+    #[serde(default)]
+    pub fly_camera: FlyCameraButtonBindings,
+
+    pub camera_stick: GamepadStick,
+    pub camera_zoom_in_button:
+        GamepadButtonType,
+    pub camera_zoom_out_button:
+        GamepadButtonType,
+
+    /// camera zoom per second while a zoom button is held.
+    pub camera_zoom_speed: f32,
+}
+
+/// keyboard bindings for the fly camera.
+#[derive(Serialize, Deserialize)]
+pub struct FlyCameraKeyBindings {
+    pub toggle_key: KeyCode,
+    pub up_key: KeyCode,
+    pub down_key: KeyCode,
+    pub fast_key: KeyCode,
+    pub slow_key: KeyCode,
+}
+
+impl Default for FlyCameraKeyBindings {
+    fn default() -> Self {
+        return FlyCameraKeyBindings {
+            toggle_key: KeyCode::KeyF,
+            up_key: KeyCode::KeyE,
+            down_key: KeyCode::KeyQ,
+            fast_key: KeyCode::ShiftLeft,
+            slow_key: KeyCode::ControlLeft,
+        };
+    }
+}
+
+/// gamepad bindings for the fly camera.
+#[derive(Serialize, Deserialize)]
+pub struct FlyCameraButtonBindings {
+    pub toggle_button: GamepadButtonType,
+    pub up_button: GamepadButtonType,
+    pub down_button: GamepadButtonType,
+    pub fast_button: GamepadButtonType,
+    pub slow_button: GamepadButtonType,
+}
+
+impl Default for FlyCameraButtonBindings {
+    fn default() -> Self {
+        return FlyCameraButtonBindings {
+            toggle_button:
+                GamepadButtonType::Select,
+            up_button:
+                GamepadButtonType::RightTrigger,
+            down_button:
+                GamepadButtonType::LeftTrigger,
+            fast_button:
+                GamepadButtonType::RightThumb,
+            slow_button:
+                GamepadButtonType::LeftThumb,
+        };
+    }
+}
+
+/// bindings from actions to the camera, regardless of device.
+#[derive(Serialize, Deserialize)]
+pub struct CameraActionBindings {
+    /// cylinder height per camera pitch.
+    pub pitch_height_scale: f32,
+
+    /// lookat offset height per camera pitch.
+    pub pitch_lookat_offset_scale: f32,
+}
+
+/// resource with bindings from input devices to actions.
+#[derive(Resource, Serialize, Deserialize)]
+pub struct InputBindingsResource {
+    pub keyboard_and_mouse:
+        KeyboardAndMouseBindings,
+    pub gamepad: GamepadBindings,
+    pub camera: CameraActionBindings,
+
+    /// gamepad parameters of each player, by player index.
+    /// nb, players without parameters, including all players of older bindings files, use the defaults.
+    #[serde(default)]
+    pub player_gamepad_parameters:
+        Vec<PlayerGamepadParametersComponent>,
+}
+
+impl InputBindingsResource {
+    /// gamepad parameters of a player.
+    /// ie, the defaults when the player has no parameters yet.
+    pub fn find_player_gamepad_parameters(
+        &self,
+        player_index: usize,
+    ) -> PlayerGamepadParametersComponent {
+        return self
+            .player_gamepad_parameters
+            .get(player_index)
+            .cloned()
+            .unwrap_or_default();
+    }
+}
+
+impl Default for InputBindingsResource {
+    fn default() -> Self {
+        return InputBindingsResource {
+            keyboard_and_mouse:
+                KeyboardAndMouseBindings {
+                    move_forward_key:
+                        KeyCode::KeyW,
+                    move_back_key:
+                        KeyCode::KeyS,
+                    move_left_key:
+                        KeyCode::KeyA,
+                    move_right_key:
+                        KeyCode::KeyD,
+                    jump_key: KeyCode::Space,
+                    fly_camera:
+                        FlyCameraKeyBindings::default(),
+                    camera_orbit_sensitivity:
+                        0.001,
+                    camera_pitch_sensitivity:
+                        0.001,
+                    camera_zoom_sensitivity:
+                        0.1,
+                },
+            gamepad: GamepadBindings {
+                move_stick:
+                    GamepadStick::Left,
+                jump_button:
+                    GamepadButtonType::South,
+                fly_camera:
+                    FlyCameraButtonBindings::default(),
+                camera_stick:
+                    GamepadStick::Right,
+                camera_zoom_in_button:
+                    GamepadButtonType::DPadUp,
+                camera_zoom_out_button:
+                    GamepadButtonType::DPadDown,
+                camera_zoom_speed: 10.0,
+            },
+            camera: CameraActionBindings {
+                pitch_height_scale: 0.5,
+                pitch_lookat_offset_scale: 0.2,
+            },
+            player_gamepad_parameters:
+                Vec::new(),
+        };
+    }
+}
+
+/// system to load [InputBindingsResource] from [INPUT_BINDINGS_PATH].
+/// when there is no file, default bindings are saved to it.
+pub fn load_input_bindings_system(
+    mut commands: Commands,
+) {
+    let input_bindings = match fs::read_to_string(INPUT_BINDINGS_PATH) {
+        Ok(input_bindings_ron) => {
+            match ron::from_str::<InputBindingsResource>(&input_bindings_ron) {
+                Ok(input_bindings) => input_bindings,
+                Err(error) => {
+                    warn!(
+                        "failed to parse {}, using default input bindings. {}",
+                        INPUT_BINDINGS_PATH, error
+                    );
+                    InputBindingsResource::default()
+                }
+            }
+        }
+        Err(_) => {
+            let input_bindings =
+                InputBindingsResource::default();
+            save_input_bindings(
+                &input_bindings,
+            );
+            input_bindings
+        }
+    };
+
+    commands
+        .insert_resource(input_bindings);
+}
+
+/// system to save [InputBindingsResource] to [INPUT_BINDINGS_PATH] when it is changed.
+/// ie, bindings changed while playing are kept for the next session.
+pub fn save_input_bindings_when_changed_system(
+    input_bindings: Res<
+        InputBindingsResource,
+    >,
+) {
+    // loaded, or saved, by load_input_bindings_system
+    if !input_bindings.is_changed()
+        || input_bindings.is_added()
+    {
+        return;
+    }
+
+    save_input_bindings(&input_bindings);
+}
+
+/// save input bindings to [INPUT_BINDINGS_PATH].
+pub fn save_input_bindings(
+    input_bindings: &InputBindingsResource,
+) {
+    let input_bindings_ron = ron::ser::to_string_pretty(
+        input_bindings,
+        ron::ser::PrettyConfig::default(),
+    )
+    .expect("Input bindings are serializable!");
+
+    if let Err(error) = fs::write(
+        INPUT_BINDINGS_PATH,
+        input_bindings_ron,
+    ) {
+        warn!(
+            "failed to save {}. {}",
+            INPUT_BINDINGS_PATH, error
+        );
+    }
+}
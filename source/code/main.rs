@@ -5,12 +5,14 @@ use bevy::{
         Startup, Update,
     },
     asset::{
-        AssetServer, Assets, Handle,
+        AssetApp, AssetServer, Assets,
+        Handle,
         LoadState, UntypedHandle,
     },
     core_pipeline::core_3d::Camera3dBundle,
     ecs::{
         entity::Entity,
+        event::EventReader,
         query::With,
         schedule::{
             common_conditions::in_state,
@@ -26,12 +28,18 @@ use bevy::{
     gizmos::gizmos::Gizmos,
     gltf::{Gltf, GltfMesh},
     hierarchy::BuildChildren,
-    input::{
-        keyboard::KeyCode, ButtonInput,
+    input::gamepad::{
+        AxisSettings,
+        GamepadConnectionEvent,
+        GamepadSettings, Gamepads,
     },
     math::{
+        cubic_splines::{
+            CubicBezier, CubicGenerator,
+        },
         primitives::{
-            Capsule3d, Cuboid,
+            Capsule3d, Cuboid, Cylinder,
+            Sphere,
         },
         Affine3A, Quat, Vec2, Vec3,
     },
@@ -44,10 +52,15 @@ use bevy::{
         PbrBundle, StandardMaterial,
     },
     render::{
-        color::Color, mesh::Mesh,
-        view::InheritedVisibility,
+        color::Color,
+        mesh::Mesh,
+        view::{
+            InheritedVisibility, Layer,
+            RenderLayers,
+        },
     },
     scene::SceneBundle,
+    time::{Fixed, Time},
     transform::{
         components::{
             GlobalTransform, Transform,
@@ -58,15 +71,11 @@ use bevy::{
     DefaultPlugins,
 };
 use bevy_rapier3d::{
-    dynamics::{
-        Ccd, Damping, GravityScale,
-        LockedAxes, RigidBody,
-        Sleeping, Velocity,
-    },
+    dynamics::{RigidBody, Velocity},
     geometry::{
         Collider, CollisionGroups,
         ComputedColliderShape,
-        Friction, Group,
+        Friction, Group, Sensor,
     },
     plugin::{
         NoUserData, PhysicsSet,
@@ -76,41 +85,94 @@ use bevy_rapier3d::{
     },
     render::RapierDebugRenderPlugin,
 };
+use camera_rail::{
+    apply_camera_rail_curves_system,
+    apply_camera_rail_using_gltf_extras_system,
+    set_desired_transform_translation_using_camera_rail_behavior_system,
+    CameraRailComponent,
+    CameraRailSpline,
+    CameraRailVariablesComponent,
+    SetDesiredTransformTranslationUsingCameraRailBehaviorComponent,
+};
+use camera_zone::{
+    apply_camera_zone_using_gltf_extras_system,
+    set_desired_transform_using_camera_zone_behavior_system,
+    CameraZoneComponent,
+    CameraZonePosition,
+    CameraZonePreset,
+    CameraZoneVariablesComponent,
+    SetDesiredTransformUsingCameraZoneBehaviorComponent,
+};
 use character::{
+    log_character_bonk_system,
     update_character_body_try_jump_while_on_stage_system,
     update_character_body_try_land_while_in_air_system,
+    update_character_body_try_ledge_getup_while_in_air_system,
+    update_character_body_rotation_while_in_air_system,
+    update_character_body_try_wall_run_while_in_air_system,
     update_character_body_velocity_while_in_air_using_movement_velocity_system,
     update_character_body_velocity_while_on_stage_using_movement_velocity_system,
+    update_character_body_while_ground_ledge_trip_system,
+    update_character_body_while_ledge_getup_system,
     update_character_body_while_on_stage_system,
+    update_character_body_while_vault_system,
     update_character_horizontal_movement_velocity_system,
+    update_character_movement_velocity_against_wall_while_on_stage_system,
     update_character_movement_velocity_while_in_air_phase_system,
     update_character_movement_velocity_while_on_stage_system,
+    update_character_wall_run_while_on_stage_system,
     CharacterBodyTagComponent,
+    CharacterBonkEvent,
     CharacterBundle,
-    CharacterFallPhaseMovementParametersComponent,
-    CharacterMovementParametersComponent,
     CharacterMovementVariablesComponent,
     CharacterPlayerInputComponent,
     CharacterTagComponent,
     CharacterTransformationFromPlayerToCameraVariablesComponent,
 };
+use character_move_shape::{
+    draw_character_move_shape_gizmos_system,
+    update_character_body_using_move_shape_system,
+    CharacterMoveShapeVariablesComponent,
+};
+use character_definition::{
+    apply_modified_character_definition_to_characters_system,
+    load_character_definition_system,
+    CharacterDefinitionAsset,
+    CharacterDefinitionAssetLoader,
+    CharacterDefinitionResource,
+    CharacterDefinitionVariablesComponent,
+};
 use cylinder_camera::{
     apply_desired_transform_using_cylinder_coordinates_system,
     apply_lookat_to_transform_system,
     draw_camera_lookat_gizmos,
+    draw_camera_parent_translation_lag_gizmos,
     set_cylinder_coordinates_for_desired_transform_translation_using_input_system,
     set_desired_lookat_position_to_observed_entity_transform_translation_with_offset_behavior_system,
     set_desired_lookat_up_to_observed_entity_transform_local_up_with_offset_behavior,
     set_desired_parent_transform_rotation_to_observed_entity_local_up_behavior_system,
     set_desired_parent_transform_translation_to_observed_entiy_transform_translation_behavior_system,
     set_lookat_offset_using_input_system,
+    restore_transform_translation_before_collision_behavior_system,
+    set_transform_translation_using_collision_behavior_system,
+    show_occluders_hidden_without_collision_behavior_system,
     transition_lookat_variables_to_desired_lookat_variables_system,
     transition_parent_transform_to_desired_parent_transform_system,
     transition_transform_to_desired_transform_system,
+    set_cylinder_coordinates_for_desired_transform_translation_using_auto_follow_behavior_system,
     ActorCameraBundle,
+    CameraAutoFollowParametersComponent,
+    CameraAutoFollowVariablesComponent,
+    CameraCollisionMode,
+    CameraCollisionParametersComponent,
+    CameraCollisionVariablesComponent,
     CameraEyesTagComponent,
+    CameraPlayerInputComponent,
+    CameraTransitionParametersComponent,
+    CameraTransitionVariablesComponent,
     CylinderActorCameraBundle,
     CylinderCoordinatesForDesiredTransformTranslationVariablesComponent,
+    CylinderTransitionParametersComponent,
     DesiredLookatVariablesComponent,
     DesiredTransformParentVariablesComponent,
     DesiredTransformVariablesComponent,
@@ -119,22 +181,83 @@ use cylinder_camera::{
     ObservedEntityVariablesComponent,
     ParentTransformVariablesComponent,
     SetCylinderCoordinateForDesiredTransformTranslationUsingInputBehaviorComponent,
+    SetCylinderCoordinatesForDesiredTransformTranslationUsingAutoFollowBehaviorComponent,
     SetDesiredLookatPositionToObservedEntityTransformTranslationWithOffsetBehaviorComponent,
     SetDesiredLookatUpToObservedEntityTransformLocalUpWithOffsetBehaviorComponent,
     SetDesiredTransformRotationToObservedEntityLocalUpBehaviorComponent,
     SetDesiredTransformTranslationToObservedEntityTransformTranslationBehaviorComponent,
     SetLookatOffsetUsingInputBehaviorComponent,
+    SetTransformTranslationUsingCollisionBehaviorComponent,
+    SmoothDampParameters,
+};
+use character_phase::{
+    log_character_phase_transition_system,
+    transition_character_phase_system,
+    update_character_phase_while_bonk_system,
+    update_character_phase_while_jumping_system,
+    update_character_phase_while_landing_system,
+    CharacterPhase,
+    CharacterPhaseEnterEvent,
+    CharacterPhaseExitEvent,
+    CharacterPhaseVariablesComponent,
+};
+use fly_camera::{
+    set_transform_using_fly_input_behavior_system,
+    FlyCameraParametersComponent,
+    FlyCameraPlayerInputComponent,
+    FlyCameraVariablesComponent,
+};
+use gravity::{
+    update_character_gravity_system,
+    CharacterGravityVariablesComponent,
+    GravityField,
+    GravityResource,
+    GravityVolumeComponent,
+};
+use input_binding::{
+    load_input_bindings_system,
+    save_input_bindings_when_changed_system,
+    InputBindingsResource,
 };
 use math::CylindricalCoordinates;
+use player::{
+    apply_camera_input_using_player_actions_system,
+    apply_character_jump_input_using_player_actions_system,
+    apply_character_movement_input_using_player_actions_system,
+    apply_player_camera_viewport_system,
+    toggle_player_camera_mode_using_player_actions_system,
+    update_character_rotation_from_player_to_character_system,
+    update_input_bindings_using_player_gamepad_parameters_system,
+    update_player_actions_using_input_bindings_system,
+    PlayerActionsComponent,
+    PlayerBundle,
+    PlayerCameraMode,
+    PlayerCameraModeVariablesComponent,
+    PlayerCameraVariablesComponent,
+    PlayerCharacterVariablesComponent,
+    PlayerGamepadParametersComponent,
+    PlayerIndexVariablesComponent,
+    PlayerInputDeviceComponent,
+    PlayerTagComponent,
+};
 
 use std::{
-    f32::consts::PI, ops::Mul,
+    f32::consts::{PI, TAU},
     time::Duration,
 };
 
+mod camera_rail;
+mod camera_zone;
 mod character;
+mod character_definition;
+mod character_move_shape;
+mod character_phase;
 mod cylinder_camera;
+mod fly_camera;
+mod gravity;
+mod input_binding;
 mod math;
+mod player;
 
 /// resource for the next zone
 #[derive(Resource)]
@@ -269,7 +392,7 @@ fn spawn_scene_using_next_zone_resource_system(
 
             // physics
             let collider =
-                Collider::from_bevy_mesh(&mesh, &ComputedColliderShape::TriMesh).unwrap();
+                Collider::from_bevy_mesh(mesh, &ComputedColliderShape::TriMesh).unwrap();
             parent_commands.spawn(collider);
         });
 
@@ -277,115 +400,6 @@ fn spawn_scene_using_next_zone_resource_system(
         .did_spawn_main_gltf = true;
 }
 
-fn update_character_rotation_from_player_to_character_system(
-    mut character_query: Query<
-        (
-            &Transform,
-            &mut CharacterTransformationFromPlayerToCameraVariablesComponent,
-        ),
-        With<CharacterTagComponent>,
-    >,
-    player_query: Query<
-        (&Transform, &GlobalTransform),
-        With<CameraEyesTagComponent>,
-    >,
-) {
-    let mut character =
-        character_query.single_mut();
-    let player = player_query.single();
-    let character_up = character.0.up();
-    let player_up = player.0.up();
-
-    let rotation_from_player_up_to_character_up =
-        Quat::from_rotation_arc(
-            *player_up,
-            *character_up,
-        );
-
-    let next_transformation = Affine3A::mul(
-        Affine3A::from_quat(rotation_from_player_up_to_character_up),
-        player.1.affine(),
-    );
-
-    character
-        .1
-        .transformation_from_screen_to_global_on_character_horizontal = next_transformation;
-}
-
-fn apply_character_movement_input_using_player_input_system(
-    keyboard_input: Res<
-        ButtonInput<KeyCode>,
-    >,
-    mut character_query: Query<
-        (
-            &CharacterTransformationFromPlayerToCameraVariablesComponent,
-            &mut CharacterPlayerInputComponent,
-        ),
-        With<CharacterTagComponent>,
-    >,
-) {
-    let mut character =
-        character_query.single_mut();
-
-    let mut local_input = Vec3::ZERO;
-    if keyboard_input
-        .pressed(KeyCode::KeyW)
-    {
-        local_input.z -= 1.0;
-    }
-
-    if keyboard_input
-        .pressed(KeyCode::KeyS)
-    {
-        local_input.z += 1.0;
-    }
-
-    if keyboard_input
-        .pressed(KeyCode::KeyD)
-    {
-        local_input.x += 1.0;
-    }
-
-    if keyboard_input
-        .pressed(KeyCode::KeyA)
-    {
-        local_input.x -= 1.0;
-    }
-
-    let next_input = Affine3A::transform_vector3(
-        &character
-            .0
-            .transformation_from_screen_to_global_on_character_horizontal,
-        local_input,
-    );
-
-    character
-        .1
-        .global_movement_player_input =
-        next_input;
-}
-
-fn apply_character_jump_input_using_player_input_system(
-    keyboard_input: Res<
-        ButtonInput<KeyCode>,
-    >,
-    mut character_query: Query<(&mut CharacterPlayerInputComponent,), With<CharacterTagComponent>>,
-) {
-    let mut character =
-        character_query.single_mut();
-
-    let mut local_input = false;
-    if keyboard_input
-        .just_pressed(KeyCode::Space)
-    {
-        local_input = true;
-    }
-    // println!("{}, {}", local_input, global_input);
-
-    character.0.do_activate_jump_input =
-        local_input
-}
-
 // endregion
 
 // region debug systems
@@ -401,22 +415,23 @@ fn draw_character_transform_gizmos_system(
         With<CharacterTagComponent>,
     >,
 ) {
-    let character =
-        character_query.single();
-
-    gizmos.arrow(
-        character.0.translation,
-        character.0.translation
-            + *character.0.right(),
-        Color::RED.with_a(0.5),
-    );
+    for character in
+        character_query.iter()
+    {
+        gizmos.arrow(
+            character.0.translation,
+            character.0.translation
+                + *character.0.right(),
+            Color::RED.with_a(0.5),
+        );
 
-    gizmos.arrow(
-        character.0.translation,
-        character.0.translation
-            + *character.0.forward(),
-        Color::BLUE.with_a(0.5),
-    );
+        gizmos.arrow(
+            character.0.translation,
+            character.0.translation
+                + *character.0.forward(),
+            Color::BLUE.with_a(0.5),
+        );
+    }
 }
 
 fn draw_character_rotation_from_global_to_character_gizmos_system(
@@ -429,35 +444,36 @@ fn draw_character_rotation_from_global_to_character_gizmos_system(
         With<CharacterTagComponent>,
     >,
 ) {
-    let character =
-        character_query.single();
-
-    let character_forward_input = Affine3A::transform_vector3(
-        &character
-            .1
-            .transformation_from_screen_to_global_on_character_horizontal,
-        Vec3::NEG_Z,
-    );
+    for character in
+        character_query.iter()
+    {
+        let character_forward_input = Affine3A::transform_vector3(
+            &character
+                .1
+                .transformation_from_screen_to_global_on_character_horizontal,
+            Vec3::NEG_Z,
+        );
 
-    let character_right_input = Affine3A::transform_vector3(
-        &character
-            .1
-            .transformation_from_screen_to_global_on_character_horizontal,
-        Vec3::X,
-    );
+        let character_right_input = Affine3A::transform_vector3(
+            &character
+                .1
+                .transformation_from_screen_to_global_on_character_horizontal,
+            Vec3::X,
+        );
 
-    gizmos.arrow(
-        character.0.translation,
-        character.0.translation
-            + character_forward_input,
-        Color::rgb(0.0, 1.0, 1.0),
-    );
-    gizmos.arrow(
-        character.0.translation,
-        character.0.translation
-            + character_right_input,
-        Color::rgb(1.0, 0.0, 1.0),
-    );
+        gizmos.arrow(
+            character.0.translation,
+            character.0.translation
+                + character_forward_input,
+            Color::rgb(0.0, 1.0, 1.0),
+        );
+        gizmos.arrow(
+            character.0.translation,
+            character.0.translation
+                + character_right_input,
+            Color::rgb(1.0, 0.0, 1.0),
+        );
+    }
 }
 
 fn draw_character_input_gizmos_system(
@@ -467,14 +483,15 @@ fn draw_character_input_gizmos_system(
         With<CharacterTagComponent>,
     >,
 ) {
-    let character =
-        character_query.single();
-
-    gizmos.arrow(
-        character.0.translation,
-        character.0.translation + character.1.global_movement_player_input,
-        Color::WHITE,
-    );
+    for character in
+        character_query.iter()
+    {
+        gizmos.arrow(
+            character.0.translation,
+            character.0.translation + character.1.global_movement_player_input,
+            Color::WHITE,
+        );
+    }
 }
 
 fn draw_character_body_velocity_gizmos_system(
@@ -484,15 +501,16 @@ fn draw_character_body_velocity_gizmos_system(
         With<CharacterTagComponent>,
     >,
 ) {
-    let character =
-        character_query.single();
-
-    gizmos.arrow(
-        character.0.translation,
-        character.0.translation
-            + character.1.linvel,
-        Color::YELLOW,
-    );
+    for character in
+        character_query.iter()
+    {
+        gizmos.arrow(
+            character.0.translation,
+            character.0.translation
+                + character.1.linvel,
+            Color::YELLOW,
+        );
+    }
 }
 
 fn draw_character_vertical_movement_velocity_gizmos_system(
@@ -502,22 +520,23 @@ fn draw_character_vertical_movement_velocity_gizmos_system(
         With<CharacterTagComponent>,
     >,
 ) {
-    let character =
-        character_query.single();
-
-    let next_body_velocity = character
-        .0
-        .up()
-        * character
-            .1
-            .local_vertical_velocity;
-
-    gizmos.arrow(
-        character.0.translation,
-        character.0.translation
-            + next_body_velocity,
-        Color::ORANGE_RED,
-    );
+    for character in
+        character_query.iter()
+    {
+        let next_body_velocity = character
+            .0
+            .up()
+            * character
+                .1
+                .local_vertical_velocity;
+
+        gizmos.arrow(
+            character.0.translation,
+            character.0.translation
+                + next_body_velocity,
+            Color::ORANGE_RED,
+        );
+    }
 }
 
 fn draw_character_horizontal_movement_velocity_gizmos_system(
@@ -527,30 +546,31 @@ fn draw_character_horizontal_movement_velocity_gizmos_system(
         With<CharacterTagComponent>,
     >,
 ) {
-    let character =
-        character_query.single();
-
-    let rotation_from_global_up_to_character_up =
-        Quat::from_rotation_arc(
-            Vec3::Y,
-            *character.0.up(),
+    for character in
+        character_query.iter()
+    {
+        let rotation_from_global_up_to_character_up =
+            Quat::from_rotation_arc(
+                Vec3::Y,
+                *character.0.up(),
+            );
+
+        let next_body_velocity = Quat::mul_vec3(
+            rotation_from_global_up_to_character_up,
+            Vec3::new(
+                character.1.global_horizontal_velocity.x,
+                0.0,
+                character.1.global_horizontal_velocity.y,
+            ),
         );
 
-    let next_body_velocity = Quat::mul_vec3(
-        rotation_from_global_up_to_character_up,
-        Vec3::new(
-            character.1.global_horizontal_velocity.x,
-            0.0,
-            character.1.global_horizontal_velocity.y,
-        ),
-    );
-
-    gizmos.arrow(
-        character.0.translation,
-        character.0.translation
-            + next_body_velocity,
-        Color::ORANGE_RED,
-    );
+        gizmos.arrow(
+            character.0.translation,
+            character.0.translation
+                + next_body_velocity,
+            Color::ORANGE_RED,
+        );
+    }
 }
 
 // endregion
@@ -560,6 +580,9 @@ fn draw_character_horizontal_movement_velocity_gizmos_system(
 fn setup_next_zone_to_suzanne_system(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
+    character_definition_resource: Res<
+        CharacterDefinitionResource,
+    >,
     mut next_app_state: ResMut<
         NextState<AppState>,
     >,
@@ -575,6 +598,10 @@ fn setup_next_zone_to_suzanne_system(
                 asset_handle
                     .clone()
                     .untyped(),
+                character_definition_resource
+                    .handle
+                    .clone()
+                    .untyped(),
             ]),
             main_gltf_asset_handle:
                 asset_handle,
@@ -661,6 +688,307 @@ fn spawn_test_zone_system(
         ),
     ));
 
+    // zone, planetoid
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(
+                Sphere::new(4.0),
+            ),
+            material: materials
+                .add(Color::WHITE),
+            transform:
+                Transform::from_xyz(
+                    0.0, 110.0, 30.0,
+                ),
+            ..default()
+        },
+        Collider::ball(4.0),
+        CollisionGroups::new(
+            Group::from_bits(0b0010)
+                .unwrap(),
+            Group::from_bits(0b0100)
+                .unwrap(),
+        ),
+    ));
+
+    // zone, planetoid gravity
+    commands.spawn((
+        TransformBundle::from_transform(
+            Transform::from_xyz(
+                0.0, 110.0, 30.0,
+            ),
+        ),
+        Collider::ball(10.0),
+        Sensor,
+        CollisionGroups::new(
+            Group::NONE,
+            Group::NONE,
+        ),
+        GravityVolumeComponent {
+            field: GravityField::Point {
+                is_outward: false,
+            },
+            priority: 0,
+        },
+    ));
+
+    // zone, log
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(
+                Cylinder::new(2.0, 12.0),
+            ),
+            material: materials
+                .add(Color::WHITE),
+            transform:
+                Transform::from_xyz(
+                    -30.0, 104.0, 0.0,
+                )
+                .with_rotation(
+                    Quat::from_rotation_x(
+                        PI / 2.0,
+                    ),
+                ),
+            ..default()
+        },
+        Collider::cylinder(6.0, 2.0),
+        CollisionGroups::new(
+            Group::from_bits(0b0010)
+                .unwrap(),
+            Group::from_bits(0b0100)
+                .unwrap(),
+        ),
+    ));
+
+    // zone, log gravity
+    commands.spawn((
+        TransformBundle::from_transform(
+            Transform::from_xyz(
+                -30.0, 104.0, 0.0,
+            )
+            .with_rotation(
+                Quat::from_rotation_x(
+                    PI / 2.0,
+                ),
+            ),
+        ),
+        Collider::cylinder(6.0, 6.0),
+        Sensor,
+        CollisionGroups::new(
+            Group::NONE,
+            Group::NONE,
+        ),
+        GravityVolumeComponent {
+            field: GravityField::Spline {
+                curve: CubicBezier::new(vec![[
+                    Vec3::new(0.0, -6.0, 0.0),
+                    Vec3::new(0.0, -2.0, 0.0),
+                    Vec3::new(0.0, 2.0, 0.0),
+                    Vec3::new(0.0, 6.0, 0.0),
+                ]])
+                .to_curve(),
+                is_outward: false,
+            },
+            priority: 0,
+        },
+    ));
+
+    // zone, log camera rail
+    // ie, the camera circles beside the log while running around it
+    commands.spawn((
+        TransformBundle::from_transform(
+            Transform::from_xyz(
+                -30.0, 104.0, 0.0,
+            ),
+        ),
+        CameraRailComponent {
+            camera_spline: CameraRailSpline::CatmullRom(
+                (-1..=9)
+                    .map(|index| {
+                        let angle = index as f32 * TAU / 8.0;
+                        return Vec3::new(
+                            f32::cos(angle) * 8.0,
+                            f32::sin(angle) * 8.0,
+                            14.0,
+                        );
+                    })
+                    .collect(),
+            ),
+            progress_spline: CameraRailSpline::CatmullRom(
+                (-1..=9)
+                    .map(|index| {
+                        let angle = index as f32 * TAU / 8.0;
+                        return Vec3::new(
+                            f32::cos(angle) * 2.0,
+                            f32::sin(angle) * 2.0,
+                            0.0,
+                        );
+                    })
+                    .collect(),
+            ),
+            maximum_distance: 4.0,
+        },
+    ));
+
+    // zone, wall
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(
+                Cuboid::new(
+                    1.0, 10.0, 10.0,
+                ),
+            ),
+            material: materials
+                .add(Color::WHITE),
+            transform:
+                Transform::from_xyz(
+                    13.0, 105.0, 0.0,
+                ),
+            ..default()
+        },
+        Collider::cuboid(0.5, 5.0, 5.0),
+        CollisionGroups::new(
+            Group::from_bits(0b0010)
+                .unwrap(),
+            Group::from_bits(0b0100)
+                .unwrap(),
+        ),
+    ));
+
+    // zone, wall gravity
+    // ie, pulls characters onto the wall
+    commands.spawn((
+        TransformBundle::from_transform(
+            Transform::from_xyz(
+                11.5, 105.0, 0.0,
+            ),
+        ),
+        Collider::cuboid(1.0, 5.0, 5.0),
+        Sensor,
+        CollisionGroups::new(
+            Group::NONE,
+            Group::NONE,
+        ),
+        GravityVolumeComponent {
+            field: GravityField::Uniform {
+                direction: Vec3::X,
+            },
+            priority: 1,
+        },
+    ));
+
+    // zone, camera rail
+    // ie, the camera tracks along the back edge of the floor
+    let back_edge_camera_rail_entity = commands.spawn((
+        TransformBundle::from_transform(
+            Transform::from_xyz(
+                0.0, 102.0, -8.0,
+            ),
+        ),
+        CameraRailComponent {
+            camera_spline: CameraRailSpline::Bezier(
+                vec![[
+                    Vec3::new(-12.0, 4.0, -10.0),
+                    Vec3::new(-4.0, 4.0, -10.0),
+                    Vec3::new(4.0, 4.0, -10.0),
+                    Vec3::new(12.0, 4.0, -10.0),
+                ]],
+            ),
+            progress_spline: CameraRailSpline::Bezier(
+                vec![[
+                    Vec3::new(-12.0, -1.0, 0.0),
+                    Vec3::new(-4.0, -1.0, 0.0),
+                    Vec3::new(4.0, -1.0, 0.0),
+                    Vec3::new(12.0, -1.0, 0.0),
+                ]],
+            ),
+            // only tracked through the zone
+            maximum_distance: 0.0,
+        },
+    )).id();
+    commands.spawn((
+        TransformBundle::from_transform(
+            Transform::from_xyz(
+                0.0, 102.0, -8.0,
+            ),
+        ),
+        Collider::cuboid(12.5, 2.5, 4.5),
+        Sensor,
+        CollisionGroups::new(
+            Group::NONE,
+            Group::NONE,
+        ),
+        CameraZoneComponent {
+            preset: CameraZonePreset {
+                position: CameraZonePosition::Rail(
+                    back_edge_camera_rail_entity,
+                ),
+                cylinder_rotation: None,
+                cylinder_distance: None,
+                cylinder_height: None,
+                lookat_position: None,
+            },
+            priority: 0,
+            blend_time: 0.5,
+        },
+    ));
+
+    // zone, camera locked to a corner of the floor
+    commands.spawn((
+        TransformBundle::from_transform(
+            Transform::from_xyz(
+                9.0, 102.0, -9.0,
+            ),
+        ),
+        Collider::cuboid(3.5, 2.5, 3.5),
+        Sensor,
+        CollisionGroups::new(
+            Group::NONE,
+            Group::NONE,
+        ),
+        CameraZoneComponent {
+            preset: CameraZonePreset {
+                position: CameraZonePosition::Fixed(
+                    Vec3::new(12.0, 108.0, -12.0),
+                ),
+                cylinder_rotation: None,
+                cylinder_distance: None,
+                cylinder_height: None,
+                lookat_position: Some(Vec3::new(
+                    9.0, 100.0, -9.0,
+                )),
+            },
+            priority: 1,
+            blend_time: 0.3,
+        },
+    ));
+
+    // zone, camera rotation locked over the obstacle side of the floor
+    commands.spawn((
+        TransformBundle::from_transform(
+            Transform::from_xyz(
+                0.0, 102.0, 8.0,
+            ),
+        ),
+        Collider::cuboid(12.5, 2.5, 4.5),
+        Sensor,
+        CollisionGroups::new(
+            Group::NONE,
+            Group::NONE,
+        ),
+        CameraZoneComponent {
+            preset: CameraZonePreset {
+                position: CameraZonePosition::Cylinder,
+                cylinder_rotation: Some(0.0),
+                cylinder_distance: Some(15.0),
+                cylinder_height: Some(5.0),
+                lookat_position: None,
+            },
+            priority: 0,
+            blend_time: 0.5,
+        },
+    ));
+
     // ambient light
     commands.insert_resource(
         AmbientLight {
@@ -691,19 +1019,26 @@ fn spawn_test_zone_system(
     });
 }
 
-fn spawn_character_system(
-    mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<
-        Assets<StandardMaterial>,
+/// spawn a character.
+/// returns the character entity.
+fn spawn_character(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<
+        StandardMaterial,
     >,
-) {
-    commands
+    character_definition_handle: Handle<
+        CharacterDefinitionAsset,
+    >,
+    character_definition: &CharacterDefinitionAsset,
+    transform: Transform,
+) -> Entity {
+    return commands
         .spawn((
             CharacterBundle {
                 tag: CharacterTagComponent,
                 global_transform: GlobalTransform::default(),
-                transform: Transform::from_xyz(0.0, 100.0, 0.0),
+                transform,
                 inherited_visibility: InheritedVisibility::default(),
                 rotation_from_player_to_character:
                     CharacterTransformationFromPlayerToCameraVariablesComponent {
@@ -714,31 +1049,65 @@ fn spawn_character_system(
                     global_movement_player_input: Vec3::ZERO,
                     do_activate_jump_input: false,
                 },
-                fall_phase_movement_parameters: CharacterFallPhaseMovementParametersComponent {
-                    maximum_down_speed: 20.0,
-                    maximum_up_speed: 25.0,
-                    down_acceleration: 0.4,
-                },
+                fall_phase_movement_parameters: character_definition
+                    .fall_phase_movement_parameters
+                    .clone(),
                 movement_variables: CharacterMovementVariablesComponent {
                     global_horizontal_velocity: Vec2::ZERO,
                     local_vertical_velocity: 0.0,
                 },
-                movement_parameters: CharacterMovementParametersComponent {
-                    global_horizontal_acceleration: 0.4,
-                    global_horizontal_drag: 0.2,
+                movement_parameters: character_definition
+                    .movement_parameters
+                    .clone(),
+                slope_movement_parameters: character_definition
+                    .slope_movement_parameters
+                    .clone(),
+                bonk_parameters: character_definition
+                    .bonk_parameters
+                    .clone(),
+                step_parameters: character_definition
+                    .step_parameters
+                    .clone(),
+                ledge_getup_parameters: character_definition
+                    .ledge_getup_parameters
+                    .clone(),
+                vault_parameters: character_definition
+                    .vault_parameters
+                    .clone(),
+                ledge_catch_parameters: character_definition
+                    .ledge_catch_parameters
+                    .clone(),
+                ledge_trip_parameters: character_definition
+                    .ledge_trip_parameters
+                    .clone(),
+                wall_run_parameters: character_definition
+                    .wall_run_parameters
+                    .clone(),
+                air_orientation_parameters: character_definition
+                    .air_orientation_parameters
+                    .clone(),
+                gravity_variables: CharacterGravityVariablesComponent {
+                    direction: Vec3::NEG_Y,
                 },
+                move_shape_parameters: character_definition
+                    .move_shape_parameters
+                    .clone(),
+                move_shape_variables:
+                    CharacterMoveShapeVariablesComponent::default(),
+                phase_variables: CharacterPhaseVariablesComponent {
+                    phase: CharacterPhase::Falling,
+                    next_phase: None,
+                    phase_time: 0.0,
+                },
+                definition_variables:
+                    CharacterDefinitionVariablesComponent {
+                        handle: character_definition_handle,
+                    },
             },
             (
-                RigidBody::Dynamic,
+                // moved by update_character_body_using_move_shape_system
+                RigidBody::KinematicPositionBased,
                 Velocity::zero(),
-                GravityScale(0.0),
-                Sleeping::disabled(),
-                Ccd::enabled(),
-                LockedAxes::ROTATION_LOCKED,
-                Damping {
-                    linear_damping: 0.0,
-                    angular_damping: 0.0,
-                },
             ),
         ))
         .with_children(|parent| {
@@ -768,21 +1137,19 @@ fn spawn_character_system(
                     combine_rule: bevy_rapier3d::dynamics::CoefficientCombineRule::Min,
                 },
             ));
-        });
+        })
+        .id();
 }
 
-fn spawn_camera_system(
-    mut commands: Commands,
-    query: Query<
-        (Entity, &Transform),
-        With<CharacterTagComponent>,
-    >,
-) {
-    let next_observed_character =
-        query.get_single().unwrap();
-
-    // camera
-    commands
+/// spawn a camera observing a character.
+/// returns the camera entity.
+fn spawn_camera(
+    commands: &mut Commands,
+    observed_entity: Entity,
+    observed_transform: Transform,
+    render_layer: Layer,
+) -> Entity {
+    return commands
         .spawn((
               ActorCameraBundle {
                 tag: CameraEyesTagComponent,
@@ -798,18 +1165,52 @@ fn spawn_camera_system(
                   up: Vec3::Y,
                 },
                 observed_entity: ObservedEntityVariablesComponent {
-                  entity: next_observed_character.0
+                  entity: observed_entity
                 },
                 lookat_offset_variables: LookatOffsetVariablesComponent {
                   translation_wrt_observed: Vec3::ZERO
                 },
+                transition_parameters: CameraTransitionParametersComponent {
+                  translation: SmoothDampParameters {
+                    smooth_time: 0.3,
+                    maximum_speed: 100.0,
+                  },
+                  rotation: SmoothDampParameters {
+                    smooth_time: 0.3,
+                    maximum_speed: 10.0,
+                  },
+                  parent_translation: SmoothDampParameters {
+                    smooth_time: 0.3,
+                    maximum_speed: 100.0,
+                  },
+                  parent_rotation: SmoothDampParameters {
+                    smooth_time: 0.3,
+                    maximum_speed: 10.0,
+                  },
+                  lookat_position: SmoothDampParameters {
+                    smooth_time: 0.3,
+                    maximum_speed: 100.0,
+                  },
+                  lookat_up: SmoothDampParameters {
+                    smooth_time: 0.3,
+                    maximum_speed: 10.0,
+                  },
+                },
+                transition_variables: CameraTransitionVariablesComponent {
+                  translation_velocity: Vec3::ZERO,
+                  rotation_velocity: Quat::from_xyzw(0.0, 0.0, 0.0, 0.0),
+                  parent_translation_velocity: Vec3::ZERO,
+                  parent_rotation_velocity: Quat::from_xyzw(0.0, 0.0, 0.0, 0.0),
+                  lookat_position_velocity: Vec3::ZERO,
+                  lookat_up_velocity: Vec3::ZERO,
+                },
               },
               CylinderActorCameraBundle {
                 parent_transform: ParentTransformVariablesComponent {
-                  transform: *next_observed_character.1
+                  transform: observed_transform
                 },
                 desired_parent_transform: DesiredTransformParentVariablesComponent {
-                  transform: *next_observed_character.1
+                  transform: observed_transform
                 },
                 cylinder_coordindates_for_desired_transform_translation_variables: CylinderCoordinatesForDesiredTransformTranslationVariablesComponent {
                     cylinder_coordindates: CylindricalCoordinates {
@@ -824,13 +1225,322 @@ fn spawn_camera_system(
                 set_lookat_offset_using_input_behavior: SetLookatOffsetUsingInputBehaviorComponent,
                 set_desired_lookat_position_to_observed_entity_transform_translation_with_offset_behavior: SetDesiredLookatPositionToObservedEntityTransformTranslationWithOffsetBehaviorComponent,
                 set_desired_lookat_up_to_observed_entity_transform_local_up_with_offset_behavior: SetDesiredLookatUpToObservedEntityTransformLocalUpWithOffsetBehaviorComponent,
+                player_input: CameraPlayerInputComponent {
+                  rotation_player_input: 0.0,
+                  height_player_input: 0.0,
+                  distance_player_input: 0.0,
+                  lookat_offset_height_player_input: 0.0,
+                },
+                cylinder_transition_parameters: CylinderTransitionParametersComponent {
+                  maximum_parent_translation_lag: Vec3::new(2.0, 4.0, 2.0),
+                },
+                collision_parameters: CameraCollisionParametersComponent {
+                  mode: CameraCollisionMode::PullIn,
+                  render_layer,
+                  radius: 0.3,
+                  ease_out: SmoothDampParameters {
+                    smooth_time: 0.5,
+                    maximum_speed: 20.0,
+                  },
+                },
+                collision_variables: CameraCollisionVariablesComponent {
+                  distance: 25.0,
+                  distance_velocity: 0.0,
+                  hidden_entities: Vec::new(),
+                  unobstructed_translation: None,
+                },
+                set_transform_translation_using_collision_behavior: SetTransformTranslationUsingCollisionBehaviorComponent,
+                camera_zone_variables: CameraZoneVariablesComponent {
+                  blends: Vec::new(),
+                },
+                set_desired_transform_using_camera_zone_behavior: SetDesiredTransformUsingCameraZoneBehaviorComponent,
+                camera_rail_variables: CameraRailVariablesComponent {
+                  rail_entity: None,
+                },
+                set_desired_transform_translation_using_camera_rail_behavior: SetDesiredTransformTranslationUsingCameraRailBehaviorComponent,
+                auto_follow_parameters: CameraAutoFollowParametersComponent {
+                  idle_time: 1.5,
+                  minimum_speed: 1.0,
+                  full_strength_speed: 8.0,
+                  rotation: SmoothDampParameters {
+                    smooth_time: 0.6,
+                    maximum_speed: 3.0,
+                  },
+                },
+                auto_follow_variables: CameraAutoFollowVariablesComponent {
+                  idle_time: 0.0,
+                  rotation_velocity: 0.0,
+                },
+                set_cylinder_coordinates_for_desired_transform_translation_using_auto_follow_behavior: SetCylinderCoordinatesForDesiredTransformTranslationUsingAutoFollowBehaviorComponent,
             },
                 Camera3dBundle {
                     transform: Transform::from_xyz(0.0, 0., 0.0)
                         .looking_at(Vec3::new(0., 0., 0.), Vec3::Y),
                     ..default()
                 },
-            ));
+                RenderLayers::layer(0).with(render_layer),
+                (
+                    FlyCameraParametersComponent {
+                        speed: 10.0,
+                        fast_speed_scale: 4.0,
+                        slow_speed_scale: 0.25,
+                        maximum_pitch: 1.5,
+                    },
+                    FlyCameraVariablesComponent {
+                        translation: Vec3::ZERO,
+                        yaw: 0.0,
+                        pitch: 0.0,
+                        stored_cylinder_coordinates: CylindricalCoordinates {
+                            distance: 25.0,
+                            rotation: 0.0,
+                            height: 5.0,
+                        },
+                    },
+                    FlyCameraPlayerInputComponent {
+                        movement_player_input: Vec3::ZERO,
+                        yaw_player_input: 0.0,
+                        pitch_player_input: 0.0,
+                        is_fast_player_input: false,
+                        is_slow_player_input: false,
+                    },
+                ),
+            ))
+            .id();
+}
+
+/// spawn a player, with their own character and camera.
+/// returns the player entity.
+#[allow(clippy::too_many_arguments)]
+fn spawn_player(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<
+        StandardMaterial,
+    >,
+    character_definition_handle: Handle<
+        CharacterDefinitionAsset,
+    >,
+    character_definition: &CharacterDefinitionAsset,
+    index: usize,
+    input_device: PlayerInputDeviceComponent,
+    gamepad_parameters: PlayerGamepadParametersComponent,
+) -> Entity {
+    let character_transform =
+        Transform::from_xyz(
+            index as f32 * 2.0,
+            100.0,
+            0.0,
+        );
+    let character_entity =
+        spawn_character(
+            commands,
+            meshes,
+            materials,
+            character_definition_handle,
+            character_definition,
+            character_transform,
+        );
+    // the default layer is shared, so each player camera renders its own layer from 1
+    let camera_entity = spawn_camera(
+        commands,
+        character_entity,
+        character_transform,
+        (index + 1) as Layer,
+    );
+
+    return commands
+        .spawn(PlayerBundle {
+            tag: PlayerTagComponent,
+            input_device,
+            gamepad_parameters,
+            actions: PlayerActionsComponent {
+                move_action: Vec2::ZERO,
+                jump_action: false,
+                camera_orbit_action: 0.0,
+                camera_pitch_action: 0.0,
+                camera_zoom_action: 0.0,
+                toggle_fly_camera_action: false,
+                fly_camera_vertical_action: 0.0,
+                fly_camera_fast_action: false,
+                fly_camera_slow_action: false,
+            },
+            index_variables:
+                PlayerIndexVariablesComponent {
+                    index: index as u32,
+                },
+            character_variables:
+                PlayerCharacterVariablesComponent {
+                    entity: character_entity,
+                },
+            camera_variables:
+                PlayerCameraVariablesComponent {
+                    entity: camera_entity,
+                },
+            camera_mode_variables:
+                PlayerCameraModeVariablesComponent {
+                    mode: PlayerCameraMode::Actor,
+                },
+        })
+        .id();
+}
+
+/// system to spawn a player, with their own character and camera, for the keyboard and each connected gamepad.
+fn spawn_players_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<
+        Assets<StandardMaterial>,
+    >,
+    gamepads: Res<Gamepads>,
+    input_bindings: Res<
+        InputBindingsResource,
+    >,
+    character_definition_resource: Res<
+        CharacterDefinitionResource,
+    >,
+    character_definitions: Res<
+        Assets<CharacterDefinitionAsset>,
+    >,
+) {
+    // loaded with the assets of the zone
+    let character_definition =
+        character_definitions
+            .get(&character_definition_resource.handle)
+            .expect("Character definition is loaded!");
+
+    let mut input_devices = Vec::from([
+        PlayerInputDeviceComponent::KeyboardAndMouse,
+    ]);
+    input_devices.extend(
+        gamepads.iter().map(
+            PlayerInputDeviceComponent::Gamepad,
+        ),
+    );
+    input_devices
+        .truncate(MAXIMUM_PLAYER_COUNT);
+
+    for (index, input_device) in
+        input_devices
+            .into_iter()
+            .enumerate()
+    {
+        spawn_player(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            character_definition_resource
+                .handle
+                .clone(),
+            character_definition,
+            index,
+            input_device,
+            input_bindings
+                .find_player_gamepad_parameters(
+                    index,
+                ),
+        );
+    }
+}
+
+/// system to spawn a player for each gamepad connected after the zone was set up.
+/// nb, players are not despawned when their gamepad disconnects, so it can reconnect.
+fn spawn_player_for_connected_gamepad_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<
+        Assets<StandardMaterial>,
+    >,
+    mut gamepad_connection_events: EventReader<
+        GamepadConnectionEvent,
+    >,
+    input_bindings: Res<
+        InputBindingsResource,
+    >,
+    character_definition_resource: Res<
+        CharacterDefinitionResource,
+    >,
+    character_definitions: Res<
+        Assets<CharacterDefinitionAsset>,
+    >,
+    player_query: Query<
+        &PlayerInputDeviceComponent,
+        With<PlayerTagComponent>,
+    >,
+) {
+    let character_definition_result =
+        character_definitions.get(
+            &character_definition_resource
+                .handle,
+        );
+
+    if character_definition_result.is_none() {
+        return;
+    }
+
+    let character_definition =
+        character_definition_result
+            .unwrap();
+
+    // players spawned this update are not in the query yet
+    let mut input_devices: Vec<
+        PlayerInputDeviceComponent,
+    > = player_query
+        .iter()
+        .copied()
+        .collect();
+
+    for gamepad_connection_event in
+        gamepad_connection_events.read()
+    {
+        if !gamepad_connection_event
+            .connected()
+        {
+            continue;
+        }
+
+        let has_player = input_devices
+            .iter()
+            .any(|input_device| {
+                return matches!(
+                    input_device,
+                    PlayerInputDeviceComponent::Gamepad(gamepad)
+                        if *gamepad == gamepad_connection_event.gamepad
+                );
+            });
+
+        if has_player
+            || input_devices.len()
+                >= MAXIMUM_PLAYER_COUNT
+        {
+            continue;
+        }
+
+        spawn_player(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            character_definition_resource
+                .handle
+                .clone(),
+            character_definition,
+            input_devices.len(),
+            PlayerInputDeviceComponent::Gamepad(
+                gamepad_connection_event
+                    .gamepad,
+            ),
+            input_bindings
+                .find_player_gamepad_parameters(
+                    input_devices.len(),
+                ),
+        );
+
+        input_devices.push(
+            PlayerInputDeviceComponent::Gamepad(
+                gamepad_connection_event
+                    .gamepad,
+            ),
+        );
+    }
 }
 
 // endregion
@@ -849,10 +1559,33 @@ struct CharacterPhaseMovementVelocitySystemSet;
 const DEFAULT_TIMESTEP: Duration =
     Duration::from_micros(15625);
 
+/// maximum amount of local players.
+/// ie, the amount of viewports the window can be split into.
+const MAXIMUM_PLAYER_COUNT: usize = 4;
+
 fn main() {
     let mut app = App::new();
 
     app.insert_state(AppState::None);
+    // dead zones are applied radially per player, instead of per axis
+    app.insert_resource(GamepadSettings {
+        default_axis_settings:
+            AxisSettings::new(
+                -1.0, 0.0, 0.0, 1.0, 0.01,
+            )
+            .unwrap(),
+        ..default()
+    });
+    // character movement is integrated using the delta of this timestep
+    app.insert_resource(
+        Time::<Fixed>::from_duration(
+            DEFAULT_TIMESTEP,
+        ),
+    );
+    // gravity outside of gravity volumes
+    app.insert_resource(GravityResource {
+        direction: Vec3::NEG_Y,
+    });
     app.insert_resource(RapierConfiguration {
         timestep_mode: TimestepMode::Fixed {
             dt: DEFAULT_TIMESTEP.as_secs_f32(),
@@ -865,20 +1598,31 @@ fn main() {
         OnEnter(AppState::SetupNextZone),
         (
             spawn_scene_using_next_zone_resource_system,
-            spawn_character_system,
-            spawn_camera_system,
+            spawn_players_system,
             spawn_test_zone_system,
         )
             .chain(),
     );
 
     app.add_plugins(DefaultPlugins);
+    app.init_asset::<CharacterDefinitionAsset>();
+    app.init_asset_loader::<CharacterDefinitionAssetLoader>();
     app.add_plugins((
         RapierPhysicsPlugin::<NoUserData>::default().in_fixed_schedule(),
         RapierDebugRenderPlugin::default(),
     ));
 
-    app.add_systems(Startup, setup_next_zone_to_suzanne_system);
+    app.add_systems(
+        Startup,
+        (
+            load_input_bindings_system,
+            (
+                load_character_definition_system,
+                setup_next_zone_to_suzanne_system,
+            )
+                .chain(),
+        ),
+    );
 
     app.add_systems(Update, transition_app_state_from_load_next_zone_to_setup_next_zone_when_next_zone_assets_loaded_system.run_if(in_state(AppState::LoadNextZone)));
     app.add_systems(
@@ -894,11 +1638,19 @@ fn main() {
             .before(CharacterPhaseMovementVelocitySystemSet),
     );
 
+    app.add_systems(
+        FixedPreUpdate,
+        update_character_gravity_system
+            .run_if(in_state(AppState::Play))
+            .before(CharacterPhaseMovementVelocitySystemSet),
+    );
+
     app.add_systems(
         FixedPreUpdate,
         (
             update_character_body_try_jump_while_on_stage_system, // leave stage before calculating horizontal velocity
             update_character_horizontal_movement_velocity_system,
+            update_character_movement_velocity_against_wall_while_on_stage_system,
             update_character_movement_velocity_while_on_stage_system,
             update_character_movement_velocity_while_in_air_phase_system,
         )
@@ -910,8 +1662,13 @@ fn main() {
     app.add_systems(
         FixedPreUpdate,
         (
+            update_character_body_while_ground_ledge_trip_system, // hold at the edge before applying velocity
             update_character_body_velocity_while_on_stage_using_movement_velocity_system,
             update_character_body_velocity_while_in_air_using_movement_velocity_system,
+            update_character_body_rotation_while_in_air_system,
+            update_character_body_while_ledge_getup_system,
+            update_character_body_while_vault_system,
+            update_character_body_using_move_shape_system, // move before finding the stage below
         )
             .chain()
             .after(CharacterPhaseMovementVelocitySystemSet)
@@ -922,17 +1679,78 @@ fn main() {
         FixedPreUpdate,
         (
             update_character_body_while_on_stage_system,
-            update_character_body_try_land_while_in_air_system,
+            (
+                update_character_body_try_land_while_in_air_system,
+                update_character_body_try_ledge_getup_while_in_air_system, // land before catching a ledge
+                update_character_body_try_wall_run_while_in_air_system, // catch a ledge before running along a wall
+            )
+                .chain(),
         )
+            .after(update_character_body_using_move_shape_system)
             .before(PhysicsSet::StepSimulation)
             .run_if(in_state(AppState::Play)),
     );
 
+    app.add_systems(
+        Update,
+        apply_modified_character_definition_to_characters_system,
+    );
+
+    app.add_systems(
+        Update,
+        (
+            update_input_bindings_using_player_gamepad_parameters_system,
+            save_input_bindings_when_changed_system,
+        )
+            .chain(),
+    );
+
+    app.add_event::<CharacterBonkEvent>();
+    app.add_event::<CharacterPhaseExitEvent>();
+    app.add_event::<CharacterPhaseEnterEvent>();
+    app.add_systems(
+        FixedPreUpdate,
+        (
+            update_character_phase_while_jumping_system,
+            update_character_phase_while_landing_system,
+            update_character_phase_while_bonk_system,
+            update_character_wall_run_while_on_stage_system,
+            transition_character_phase_system,
+        )
+            .chain()
+            .after(CharacterPhaseMovementVelocitySystemSet)
+            .after(update_character_body_while_on_stage_system)
+            .after(update_character_body_try_land_while_in_air_system)
+            .after(update_character_body_try_ledge_getup_while_in_air_system)
+            .after(update_character_body_try_wall_run_while_in_air_system)
+            .after(update_character_body_while_ledge_getup_system)
+            .after(update_character_body_while_vault_system)
+            .after(update_character_body_while_ground_ledge_trip_system)
+            .run_if(in_state(AppState::Play)),
+    );
+
+    app.add_systems(
+        Update,
+        spawn_player_for_connected_gamepad_system
+            .run_if(in_state(AppState::Play)),
+    );
+
     app.add_systems(
         Update,
         (
-            apply_character_movement_input_using_player_input_system,
-            apply_character_jump_input_using_player_input_system,
+            (
+                update_player_actions_using_input_bindings_system,
+                toggle_player_camera_mode_using_player_actions_system,
+                (
+                    apply_character_movement_input_using_player_actions_system,
+                    apply_character_jump_input_using_player_actions_system,
+                    apply_camera_input_using_player_actions_system,
+                ),
+            )
+                .chain()
+                .before(set_lookat_offset_using_input_system)
+                .before(set_cylinder_coordinates_for_desired_transform_translation_using_input_system),
+            apply_player_camera_viewport_system,
         )
             .run_if(in_state(AppState::Play)),
     );
@@ -949,6 +1767,57 @@ fn main() {
             .run_if(in_state(AppState::Play)),
     );
 
+    app.add_systems(
+        FixedUpdate,
+        set_desired_transform_translation_using_camera_rail_behavior_system
+            .after(apply_desired_transform_using_cylinder_coordinates_system)
+            .before(set_desired_transform_using_camera_zone_behavior_system)
+            .before(transition_transform_to_desired_transform_system)
+            .run_if(in_state(AppState::Play)),
+    );
+
+    app.add_systems(
+        FixedUpdate,
+        set_desired_transform_using_camera_zone_behavior_system
+            .after(apply_desired_transform_using_cylinder_coordinates_system)
+            .after(set_desired_lookat_position_to_observed_entity_transform_translation_with_offset_behavior_system)
+            .before(transition_transform_to_desired_transform_system)
+            .before(transition_lookat_variables_to_desired_lookat_variables_system)
+            .run_if(in_state(AppState::Play)),
+    );
+
+    app.add_systems(
+        Update,
+        (
+            apply_camera_zone_using_gltf_extras_system,
+            apply_camera_rail_using_gltf_extras_system
+                .before(apply_camera_rail_curves_system),
+            apply_camera_rail_curves_system,
+        ),
+    );
+
+    app.add_systems(
+        FixedUpdate,
+        set_transform_translation_using_collision_behavior_system
+            .after(transition_transform_to_desired_transform_system)
+            .after(transition_lookat_variables_to_desired_lookat_variables_system)
+            .run_if(in_state(AppState::Play)),
+    );
+
+    app.add_systems(
+        FixedUpdate,
+        restore_transform_translation_before_collision_behavior_system
+            .before(transition_transform_to_desired_transform_system)
+            .run_if(in_state(AppState::Play)),
+    );
+
+    app.add_systems(
+        FixedUpdate,
+        show_occluders_hidden_without_collision_behavior_system
+            .after(set_transform_translation_using_collision_behavior_system)
+            .run_if(in_state(AppState::Play)),
+    );
+
     app.add_systems(
         Update,
         (
@@ -962,6 +1831,31 @@ fn main() {
             .run_if(in_state(AppState::Play)),
     );
 
+    app.add_systems(
+        Update,
+        set_cylinder_coordinates_for_desired_transform_translation_using_auto_follow_behavior_system
+            .after(set_cylinder_coordinates_for_desired_transform_translation_using_input_system)
+            .run_if(in_state(AppState::Play)),
+    );
+
+    // fly camera overrides the actor camera transform
+    app.add_systems(
+        Update,
+        set_transform_using_fly_input_behavior_system
+            .after(apply_lookat_to_transform_system)
+            .after(apply_camera_input_using_player_actions_system)
+            .run_if(in_state(AppState::Play)),
+    );
+
+    app.add_systems(
+        PostUpdate,
+        (
+            log_character_phase_transition_system,
+            log_character_bonk_system,
+        )
+            .run_if(in_state(AppState::Play)),
+    );
+
     app.add_systems(
         PostUpdate,
         (
@@ -971,7 +1865,9 @@ fn main() {
             draw_character_body_velocity_gizmos_system,
             draw_character_horizontal_movement_velocity_gizmos_system,
             draw_character_vertical_movement_velocity_gizmos_system,
-            draw_camera_lookat_gizmos
+            draw_character_move_shape_gizmos_system,
+            draw_camera_lookat_gizmos,
+            draw_camera_parent_translation_lag_gizmos,
         )
             .run_if(in_state(AppState::Play)),
     );
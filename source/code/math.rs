@@ -1,7 +1,11 @@
-use bevy::math::{Vec2, Vec3};
+use bevy::math::{
+    cubic_splines::CubicCurve,
+    Quat, Vec2, Vec3, Vec4,
+};
 
 // TODO contribute to Bevy
 
+#[derive(Clone, Copy)]
 pub struct CylindricalCoordinates {
     // distance to the center
     pub distance: f32,
@@ -11,30 +15,12 @@ pub struct CylindricalCoordinates {
     pub height: f32,
 }
 
-impl CylindricalCoordinates {
-    pub fn new(
-        distance: f32,
-        rotation: f32,
-        height: f32,
-    ) -> Self {
-        return CylindricalCoordinates {
-            distance,
-            rotation,
-            height,
-        };
-    }
-}
-
 pub trait FromCylindrical {
     fn from_cylindrical(
         cylindrical: &CylindricalCoordinates,
     ) -> Self;
 }
 
-pub trait FromVec3 {
-    fn from_vec3(vec3: &Vec3) -> Self;
-}
-
 impl FromCylindrical for Vec3 {
     fn from_cylindrical(
         c: &CylindricalCoordinates,
@@ -56,22 +42,6 @@ impl FromCylindrical for Vec3 {
     }
 }
 
-impl FromVec3
-    for CylindricalCoordinates
-{
-    #[inline]
-    fn from_vec3(v: &Vec3) -> Self {
-        let distance = f32::sqrt(
-            v.x * v.x + v.z * v.z,
-        );
-        let height = v.y;
-        let rotation =
-            f32::atan2(v.x, v.z); // angle of vector
-
-        return CylindricalCoordinates::new(distance, rotation, height);
-    }
-}
-
 // TODO move smooth damp to own module
 
 pub trait SmoothDamp
@@ -88,42 +58,6 @@ where
     ) -> (Self, Self);
 }
 
-pub trait Slerp {
-    fn slerp(
-        &self,
-        end: Vec3,
-        s: f32,
-    ) -> Self;
-}
-
-impl Slerp for Vec3 {
-    fn slerp(
-        &self,
-        end: Vec3,
-        s: f32,
-    ) -> Vec3 {
-        let mut dot: f32 =
-            Vec3::dot(*self, end);
-
-        dot =
-            f32::clamp(dot, -1.0, 1.0);
-
-        let theta: f32 =
-            f32::acos(dot) * s;
-
-        let mut relative_vec: Vec3 =
-            end - (*self * dot);
-
-        relative_vec = relative_vec
-            .normalize_or_zero();
-
-        return (*self
-            * f32::cos(theta))
-            + (relative_vec
-                * f32::sin(theta));
-    }
-}
-
 impl SmoothDamp for Vec3 {
     fn smooth_damp(
         self,
@@ -330,6 +264,86 @@ impl SmoothDamp for f32 {
     }
 }
 
+impl SmoothDamp for Quat {
+    fn smooth_damp(
+        self,
+        target: Self,
+        current_velocity: Quat,
+        smooth_time: f32,
+        max_speed: f32,
+        delta_time: f32,
+    ) -> (Self, Quat) {
+        // smooth damp each component, then renormalize
+        // take the shortest path
+        let target = if Quat::dot(
+            self, target,
+        ) > 0.0
+        {
+            target
+        } else {
+            -target
+        };
+
+        let (output_x, velocity_x) =
+            f32::smooth_damp(
+                self.x,
+                target.x,
+                current_velocity.x,
+                smooth_time,
+                max_speed,
+                delta_time,
+            );
+        let (output_y, velocity_y) =
+            f32::smooth_damp(
+                self.y,
+                target.y,
+                current_velocity.y,
+                smooth_time,
+                max_speed,
+                delta_time,
+            );
+        let (output_z, velocity_z) =
+            f32::smooth_damp(
+                self.z,
+                target.z,
+                current_velocity.z,
+                smooth_time,
+                max_speed,
+                delta_time,
+            );
+        let (output_w, velocity_w) =
+            f32::smooth_damp(
+                self.w,
+                target.w,
+                current_velocity.w,
+                smooth_time,
+                max_speed,
+                delta_time,
+            );
+
+        let output = Vec4::new(
+            output_x, output_y,
+            output_z, output_w,
+        )
+        .normalize();
+
+        // keep velocity tangent to the unit hypersphere
+        let mut velocity = Vec4::new(
+            velocity_x, velocity_y,
+            velocity_z, velocity_w,
+        );
+        velocity -= output
+            * Vec4::dot(
+                velocity, output,
+            );
+
+        return (
+            Quat::from_vec4(output),
+            Quat::from_vec4(velocity),
+        );
+    }
+}
+
 pub trait MoveTowards {
     fn move_towards(
         self,
@@ -445,3 +459,257 @@ impl MoveTowards for Vec3 {
         );
     }
 }
+
+/// parameter of the nearest point to a point on a cubic curve.
+/// ie, from 0 to the number of segments of the curve.
+/// approximate, by sampling the curve into subdivisions for each segment.
+/// none when the curve has no segments.
+pub fn find_nearest_parameter_on_curve(
+    curve: &CubicCurve<Vec3>,
+    point: Vec3,
+    subdivisions_per_segment: usize,
+) -> Option<f32> {
+    let segment_count =
+        curve.segments().len();
+
+    if segment_count == 0 {
+        return None;
+    }
+
+    let subdivisions = segment_count
+        * usize::max(
+            subdivisions_per_segment,
+            1,
+        );
+    let step = segment_count as f32
+        / subdivisions as f32;
+
+    let samples: Vec<Vec3> = curve
+        .iter_positions(subdivisions)
+        .collect();
+
+    return samples
+        .windows(2)
+        .enumerate()
+        .map(|(index, sample)| {
+            let sample_direction =
+                sample[1] - sample[0];
+            let sample_length_squared =
+                sample_direction
+                    .length_squared();
+
+            let fraction =
+                if sample_length_squared
+                    <= 0.0
+                {
+                    0.0
+                } else {
+                    f32::clamp(
+                        Vec3::dot(
+                            point - sample[0],
+                            sample_direction,
+                        ) / sample_length_squared,
+                        0.0,
+                        1.0,
+                    )
+                };
+
+            return (
+                (index as f32 + fraction)
+                    * step,
+                (sample[0]
+                    + sample_direction
+                        * fraction)
+                    .distance_squared(point),
+            );
+        })
+        .min_by(|a, b| {
+            f32::total_cmp(&a.1, &b.1)
+        })
+        .map(|nearest| nearest.0);
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::{
+        cubic_splines::{
+            CubicBezier, CubicGenerator,
+        },
+        Quat, Vec3,
+    };
+
+    use super::{
+        find_nearest_parameter_on_curve,
+        SmoothDamp,
+    };
+
+    /// smooth damp a rotation for a number of fixed steps.
+    /// returns the rotation after each step.
+    fn smooth_damp_rotation(
+        rotation: Quat,
+        target: Quat,
+        steps: usize,
+    ) -> Vec<Quat> {
+        let mut rotation = rotation;
+        let mut velocity =
+            Quat::from_xyzw(0.0, 0.0, 0.0, 0.0);
+
+        return (0..steps)
+            .map(|_| {
+                (rotation, velocity) =
+                    Quat::smooth_damp(
+                        rotation,
+                        target,
+                        velocity,
+                        0.2,
+                        f32::INFINITY,
+                        1.0 / 60.0,
+                    );
+                return rotation;
+            })
+            .collect();
+    }
+
+    #[test]
+    fn smooth_damp_rotation_converges_to_target(
+    ) {
+        let target = Quat::from_rotation_y(
+            2.0,
+        ) * Quat::from_rotation_x(0.5);
+        let rotations = smooth_damp_rotation(
+            Quat::IDENTITY,
+            target,
+            300,
+        );
+
+        assert!(
+            Quat::angle_between(
+                *rotations.last().unwrap(),
+                target
+            ) < 1e-3
+        );
+    }
+
+    #[test]
+    fn smooth_damp_rotation_takes_shortest_path_to_opposite_hemisphere_target(
+    ) {
+        let target =
+            Quat::from_rotation_z(1.0);
+        let rotations = smooth_damp_rotation(
+            Quat::IDENTITY,
+            target,
+            300,
+        );
+        let opposite_hemisphere_rotations =
+            smooth_damp_rotation(
+                Quat::IDENTITY,
+                -target,
+                300,
+            );
+
+        // same rotations, each closer to the target than the last
+        let mut previous_angle =
+            Quat::angle_between(
+                Quat::IDENTITY,
+                target,
+            );
+        for (rotation, opposite_hemisphere_rotation) in
+            rotations.iter().zip(
+                opposite_hemisphere_rotations
+                    .iter(),
+            )
+        {
+            assert!(rotation.abs_diff_eq(
+                *opposite_hemisphere_rotation,
+                1e-6
+            ));
+
+            let angle = Quat::angle_between(
+                *rotation, target,
+            );
+            assert!(
+                angle <= previous_angle + 1e-6
+            );
+            previous_angle = angle;
+        }
+    }
+
+    #[test]
+    fn smooth_damp_rotation_is_unit_quaternion(
+    ) {
+        let rotations = smooth_damp_rotation(
+            Quat::from_rotation_x(-1.0),
+            Quat::from_rotation_y(3.0),
+            120,
+        );
+
+        for rotation in rotations.iter() {
+            assert!(rotation.is_normalized());
+        }
+    }
+
+    #[test]
+    fn nearest_parameter_on_curve_is_found_in_each_segment(
+    ) {
+        // straight segments, with control points at thirds
+        // ie, the parameter is linear in distance along each segment
+        let curve = CubicBezier::new(vec![
+            [
+                Vec3::new(0.0, 0.0, 0.0),
+                Vec3::new(4.0, 0.0, 0.0),
+                Vec3::new(8.0, 0.0, 0.0),
+                Vec3::new(12.0, 0.0, 0.0),
+            ],
+            [
+                Vec3::new(12.0, 0.0, 0.0),
+                Vec3::new(12.0, 0.0, 4.0),
+                Vec3::new(12.0, 0.0, 8.0),
+                Vec3::new(12.0, 0.0, 12.0),
+            ],
+        ])
+        .to_curve();
+
+        let parameter =
+            find_nearest_parameter_on_curve(
+                &curve,
+                Vec3::new(6.0, 2.0, -1.0),
+                16,
+            )
+            .unwrap();
+        assert!((parameter - 0.5).abs() < 1e-4);
+
+        let parameter =
+            find_nearest_parameter_on_curve(
+                &curve,
+                Vec3::new(14.0, 0.0, 3.0),
+                16,
+            )
+            .unwrap();
+        assert!((parameter - 1.25).abs() < 1e-4);
+
+        let parameter =
+            find_nearest_parameter_on_curve(
+                &curve,
+                Vec3::new(12.0, 0.0, 20.0),
+                16,
+            )
+            .unwrap();
+        assert!((parameter - 2.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn nearest_parameter_on_curve_is_none_without_segments(
+    ) {
+        let curve = CubicBezier::new(
+            Vec::<[Vec3; 4]>::new(),
+        )
+        .to_curve();
+
+        assert!(find_nearest_parameter_on_curve(
+            &curve,
+            Vec3::ZERO,
+            16
+        )
+        .is_none());
+    }
+}
@@ -0,0 +1,1164 @@
+use bevy::{
+    ecs::{
+        bundle::Bundle,
+        component::Component,
+        entity::Entity,
+        event::EventReader,
+        query::{Changed, With, Without},
+        system::{
+            Commands, Query, Res, ResMut,
+        },
+    },
+    input::{
+        gamepad::{
+            Gamepad, GamepadAxis,
+            GamepadButton,
+        },
+        keyboard::KeyCode,
+        mouse::{
+            MouseMotion, MouseWheel,
+        },
+        Axis, ButtonInput,
+    },
+    math::{
+        Affine3A, EulerRot, Quat, UVec2,
+        Vec2, Vec3,
+    },
+    render::camera::{
+        Camera, Viewport,
+    },
+    time::Time,
+    transform::components::{
+        GlobalTransform, Transform,
+    },
+    window::{PrimaryWindow, Window},
+};
+use serde::{Deserialize, Serialize};
+use std::ops::Mul;
+
+use crate::{
+    input_binding::InputBindingsResource,
+    character::{
+        CharacterPlayerInputComponent,
+        CharacterTagComponent,
+        CharacterTransformationFromPlayerToCameraVariablesComponent,
+    },
+    cylinder_camera::{
+        CameraEyesTagComponent,
+        CameraPlayerInputComponent,
+        CylinderActorCameraBehaviorBundle,
+        CylinderCoordinatesForDesiredTransformTranslationVariablesComponent,
+    },
+    fly_camera::{
+        FlyCameraPlayerInputComponent,
+        FlyCameraVariablesComponent,
+        SetTransformUsingFlyInputBehaviorComponent,
+    },
+};
+
+/// tag component for "player" entity.
+/// ie, a person playing locally, which controls a character using a camera.
+#[derive(Component)]
+pub struct PlayerTagComponent;
+
+/// component with the input device used by a player.
+#[derive(Component, Clone, Copy)]
+pub enum PlayerInputDeviceComponent {
+    KeyboardAndMouse,
+    Gamepad(Gamepad),
+}
+
+/// parameters for the response of an analog stick.
+#[derive(
+    Clone,
+    PartialEq,
+    Serialize,
+    Deserialize,
+)]
+pub struct StickResponseParameters {
+    /// magnitude below which the stick is considered centered.
+    pub inner_dead_zone: f32,
+
+    /// magnitude above which the stick is considered fully tilted.
+    pub outer_dead_zone: f32,
+
+    /// exponent of the response curve applied to the magnitude, after dead zones.
+    /// ie, 1.0 is linear, greater values give finer control near the center.
+    pub response_exponent: f32,
+}
+
+impl StickResponseParameters {
+    /// apply dead zones and response curve to stick input.
+    /// the direction of the input is preserved, only the magnitude is changed.
+    pub fn apply(
+        &self,
+        stick_input: Vec2,
+    ) -> Vec2 {
+        let magnitude =
+            stick_input.length();
+
+        if magnitude
+            <= self.inner_dead_zone
+        {
+            return Vec2::ZERO;
+        }
+
+        let live_magnitude = f32::clamp(
+            (magnitude
+                - self.inner_dead_zone)
+                / f32::max(
+                    self.outer_dead_zone
+                        - self.inner_dead_zone,
+                    f32::EPSILON,
+                ),
+            0.0,
+            1.0,
+        );
+
+        return stick_input / magnitude
+            * f32::powf(
+                live_magnitude,
+                self.response_exponent,
+            );
+    }
+}
+
+/// component with parameters for gamepad input of a player.
+/// ie, each player tunes their own sticks, while button bindings are shared.
+/// nb, loaded from and saved to [InputBindingsResource] by player index.
+#[derive(
+    Component,
+    Clone,
+    PartialEq,
+    Serialize,
+    Deserialize,
+)]
+pub struct PlayerGamepadParametersComponent
+{
+    /// response of the stick used for character movement.
+    pub move_stick_response:
+        StickResponseParameters,
+
+    /// response of the stick used for the camera.
+    pub camera_stick_response:
+        StickResponseParameters,
+
+    /// camera orbit per second while the camera stick is fully tilted.
+    pub camera_orbit_speed: f32,
+
+    /// camera pitch per second while the camera stick is fully tilted.
+    pub camera_pitch_speed: f32,
+}
+
+impl Default
+    for PlayerGamepadParametersComponent
+{
+    fn default() -> Self {
+        return PlayerGamepadParametersComponent {
+            move_stick_response:
+                StickResponseParameters {
+                    inner_dead_zone: 0.15,
+                    outer_dead_zone: 0.95,
+                    response_exponent: 1.0,
+                },
+            camera_stick_response:
+                StickResponseParameters {
+                    inner_dead_zone: 0.15,
+                    outer_dead_zone: 0.95,
+                    response_exponent: 2.0,
+                },
+            camera_orbit_speed: 2.0,
+            camera_pitch_speed: 1.0,
+        };
+    }
+}
+
+/// component with actions of a player, resolved from their input device using [InputBindingsResource].
+#[derive(Component)]
+pub struct PlayerActionsComponent {
+    /// movement, in screen space.
+    /// ie, magnitude is preserved for analog movement.
+    pub move_action: Vec2,
+
+    /// jump was activated this update.
+    pub jump_action: bool,
+
+    /// amount to orbit the camera around the character.
+    pub camera_orbit_action: f32,
+
+    /// amount to pitch the camera, positive is downwards on screen.
+    pub camera_pitch_action: f32,
+
+    /// amount to zoom the camera, positive is inwards.
+    pub camera_zoom_action: f32,
+
+    /// toggle of fly camera was activated this update.
+    pub toggle_fly_camera_action: bool,
+
+    /// vertical movement of fly camera, positive is upwards.
+    pub fly_camera_vertical_action: f32,
+
+    pub fly_camera_fast_action: bool,
+    pub fly_camera_slow_action: bool,
+}
+
+/// mode of the camera of a player.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum PlayerCameraMode {
+    /// cylinder actor camera, observing the player's character.
+    Actor,
+
+    /// free camera for debugging.
+    /// ie, character input is suspended.
+    Fly,
+}
+
+/// component with variables for the mode of the camera of a player.
+#[derive(Component)]
+pub struct PlayerCameraModeVariablesComponent
+{
+    pub mode: PlayerCameraMode,
+}
+
+/// component with variables for the index of a player.
+/// ie, the order of the player, used for their viewport.
+#[derive(Component)]
+pub struct PlayerIndexVariablesComponent
+{
+    pub index: u32,
+}
+
+/// component with variables for the character controlled by a player.
+#[derive(Component)]
+pub struct PlayerCharacterVariablesComponent
+{
+    pub entity: Entity,
+}
+
+/// component with variables for the camera which belongs to a player.
+#[derive(Component)]
+pub struct PlayerCameraVariablesComponent
+{
+    pub entity: Entity,
+}
+
+/// bundle for "player" entity
+#[derive(Bundle)]
+pub struct PlayerBundle {
+    pub tag: PlayerTagComponent,
+    pub input_device:
+        PlayerInputDeviceComponent,
+    pub gamepad_parameters:
+        PlayerGamepadParametersComponent,
+    pub actions: PlayerActionsComponent,
+    pub index_variables:
+        PlayerIndexVariablesComponent,
+    pub character_variables:
+        PlayerCharacterVariablesComponent,
+    pub camera_variables:
+        PlayerCameraVariablesComponent,
+    pub camera_mode_variables:
+        PlayerCameraModeVariablesComponent,
+}
+
+// region apply system
+
+/// system to keep the gamepad parameters of each player in [InputBindingsResource].
+/// ie, parameters of new players, or changed while playing, are saved with the bindings.
+pub fn update_input_bindings_using_player_gamepad_parameters_system(
+    mut input_bindings: ResMut<
+        InputBindingsResource,
+    >,
+    player_query: Query<
+        (
+            &PlayerIndexVariablesComponent,
+            &PlayerGamepadParametersComponent,
+        ),
+        (
+            With<PlayerTagComponent>,
+            Changed<
+                PlayerGamepadParametersComponent,
+            >,
+        ),
+    >,
+) {
+    for (
+        player_index_variables,
+        player_gamepad_parameters,
+    ) in player_query.iter()
+    {
+        let player_index =
+            player_index_variables.index
+                as usize;
+
+        // unchanged, so the bindings are not saved again
+        if input_bindings
+            .player_gamepad_parameters
+            .get(player_index)
+            == Some(player_gamepad_parameters)
+        {
+            continue;
+        }
+
+        if input_bindings
+            .player_gamepad_parameters
+            .len()
+            <= player_index
+        {
+            input_bindings
+                .player_gamepad_parameters
+                .resize_with(
+                    player_index + 1,
+                    PlayerGamepadParametersComponent::default,
+                );
+        }
+
+        input_bindings
+            .player_gamepad_parameters
+            [player_index] =
+            player_gamepad_parameters
+                .clone();
+    }
+}
+
+/// system to update the transformation from player to character, using the camera of the player for that character.
+pub fn update_character_rotation_from_player_to_character_system(
+    player_query: Query<
+        (
+            &PlayerCharacterVariablesComponent,
+            &PlayerCameraVariablesComponent,
+        ),
+        With<PlayerTagComponent>,
+    >,
+    mut character_query: Query<
+        (
+            &Transform,
+            &mut CharacterTransformationFromPlayerToCameraVariablesComponent,
+        ),
+        With<CharacterTagComponent>,
+    >,
+    camera_query: Query<
+        (&Transform, &GlobalTransform),
+        (
+            With<CameraEyesTagComponent>,
+            Without<CharacterTagComponent>,
+        ),
+    >,
+) {
+    for (
+        player_character,
+        player_camera,
+    ) in player_query.iter()
+    {
+        let character_result =
+            character_query.get_mut(
+                player_character.entity,
+            );
+        let camera_result = camera_query
+            .get(player_camera.entity);
+
+        if character_result.is_err()
+            || camera_result.is_err()
+        {
+            continue;
+        }
+
+        let mut character =
+            character_result.unwrap();
+        let camera =
+            camera_result.unwrap();
+        let character_up =
+            character.0.up();
+        let camera_up = camera.0.up();
+
+        let rotation_from_camera_up_to_character_up =
+            Quat::from_rotation_arc(
+                *camera_up,
+                *character_up,
+            );
+
+        let next_transformation = Affine3A::mul(
+            Affine3A::from_quat(rotation_from_camera_up_to_character_up),
+            camera.1.affine(),
+        );
+
+        character
+            .1
+            .transformation_from_screen_to_global_on_character_horizontal = next_transformation;
+    }
+}
+
+/// system to update [PlayerActionsComponent] using the input device of each player and [InputBindingsResource].
+#[allow(clippy::too_many_arguments)]
+pub fn update_player_actions_using_input_bindings_system(
+    time: Res<Time>,
+    input_bindings: Res<
+        InputBindingsResource,
+    >,
+    keyboard_input: Res<
+        ButtonInput<KeyCode>,
+    >,
+    mut mouse_motion_events: EventReader<MouseMotion>,
+    mut mouse_wheel_events: EventReader<
+        MouseWheel,
+    >,
+    gamepad_axes: Res<
+        Axis<GamepadAxis>,
+    >,
+    gamepad_buttons: Res<
+        ButtonInput<GamepadButton>,
+    >,
+    mut player_query: Query<
+        (
+            &PlayerInputDeviceComponent,
+            &PlayerGamepadParametersComponent,
+            &mut PlayerActionsComponent,
+        ),
+        With<PlayerTagComponent>,
+    >,
+) {
+    let keyboard_and_mouse_bindings =
+        &input_bindings
+            .keyboard_and_mouse;
+    let gamepad_bindings =
+        &input_bindings.gamepad;
+
+    let mut mouse_motion = Vec2::ZERO;
+    for mouse_event in
+        mouse_motion_events.read()
+    {
+        mouse_motion +=
+            mouse_event.delta;
+    }
+
+    let mut mouse_wheel_motion: f32 =
+        0.0;
+    for mouse_event in
+        mouse_wheel_events.read()
+    {
+        mouse_wheel_motion +=
+            mouse_event.y;
+    }
+
+    for (
+        player_input_device,
+        player_gamepad_parameters,
+        mut player_actions,
+    ) in player_query.iter_mut()
+    {
+        match player_input_device {
+            PlayerInputDeviceComponent::KeyboardAndMouse => {
+                let mut move_action =
+                    Vec2::ZERO;
+                if keyboard_input.pressed(
+                    keyboard_and_mouse_bindings
+                        .move_forward_key,
+                ) {
+                    move_action.y += 1.0;
+                }
+
+                if keyboard_input.pressed(
+                    keyboard_and_mouse_bindings
+                        .move_back_key,
+                ) {
+                    move_action.y -= 1.0;
+                }
+
+                if keyboard_input.pressed(
+                    keyboard_and_mouse_bindings
+                        .move_right_key,
+                ) {
+                    move_action.x += 1.0;
+                }
+
+                if keyboard_input.pressed(
+                    keyboard_and_mouse_bindings
+                        .move_left_key,
+                ) {
+                    move_action.x -= 1.0;
+                }
+
+                player_actions.move_action =
+                    move_action;
+                player_actions.jump_action =
+                    keyboard_input
+                        .just_pressed(
+                            keyboard_and_mouse_bindings
+                                .jump_key,
+                        );
+                player_actions
+                    .camera_orbit_action =
+                    mouse_motion.x
+                        * keyboard_and_mouse_bindings
+                            .camera_orbit_sensitivity;
+                player_actions
+                    .camera_pitch_action =
+                    mouse_motion.y
+                        * keyboard_and_mouse_bindings
+                            .camera_pitch_sensitivity;
+                player_actions
+                    .camera_zoom_action =
+                    mouse_wheel_motion
+                        * keyboard_and_mouse_bindings
+                            .camera_zoom_sensitivity;
+
+                let mut fly_camera_vertical_action: f32 = 0.0;
+                if keyboard_input.pressed(
+                    keyboard_and_mouse_bindings
+                        .fly_camera
+                        .up_key,
+                ) {
+                    fly_camera_vertical_action += 1.0;
+                }
+
+                if keyboard_input.pressed(
+                    keyboard_and_mouse_bindings
+                        .fly_camera
+                        .down_key,
+                ) {
+                    fly_camera_vertical_action -= 1.0;
+                }
+
+                player_actions
+                    .toggle_fly_camera_action =
+                    keyboard_input
+                        .just_pressed(
+                            keyboard_and_mouse_bindings
+                                .fly_camera
+                                .toggle_key,
+                        );
+                player_actions
+                    .fly_camera_vertical_action =
+                    fly_camera_vertical_action;
+                player_actions
+                    .fly_camera_fast_action =
+                    keyboard_input.pressed(
+                        keyboard_and_mouse_bindings
+                            .fly_camera
+                            .fast_key,
+                    );
+                player_actions
+                    .fly_camera_slow_action =
+                    keyboard_input.pressed(
+                        keyboard_and_mouse_bindings
+                            .fly_camera
+                            .slow_key,
+                    );
+            }
+            PlayerInputDeviceComponent::Gamepad(gamepad) => {
+                let move_axis_types =
+                    gamepad_bindings
+                        .move_stick
+                        .axis_types();
+                let move_stick_input = Vec2::new(
+                    gamepad_axes
+                        .get(GamepadAxis::new(*gamepad, move_axis_types.0))
+                        .unwrap_or(0.0),
+                    gamepad_axes
+                        .get(GamepadAxis::new(*gamepad, move_axis_types.1))
+                        .unwrap_or(0.0),
+                );
+
+                let camera_axis_types =
+                    gamepad_bindings
+                        .camera_stick
+                        .axis_types();
+                let camera_stick_input = player_gamepad_parameters
+                    .camera_stick_response
+                    .apply(Vec2::new(
+                        gamepad_axes
+                            .get(GamepadAxis::new(*gamepad, camera_axis_types.0))
+                            .unwrap_or(0.0),
+                        gamepad_axes
+                            .get(GamepadAxis::new(*gamepad, camera_axis_types.1))
+                            .unwrap_or(0.0),
+                    ));
+
+                let mut zoom_input: f32 =
+                    0.0;
+                if gamepad_buttons.pressed(
+                    GamepadButton::new(
+                        *gamepad,
+                        gamepad_bindings
+                            .camera_zoom_in_button,
+                    ),
+                ) {
+                    zoom_input += 1.0;
+                }
+
+                if gamepad_buttons.pressed(
+                    GamepadButton::new(
+                        *gamepad,
+                        gamepad_bindings
+                            .camera_zoom_out_button,
+                    ),
+                ) {
+                    zoom_input -= 1.0;
+                }
+
+                let delta_time = time
+                    .delta()
+                    .as_secs_f32();
+
+                // magnitude of the stick is preserved, for analog movement
+                player_actions.move_action =
+                    player_gamepad_parameters
+                        .move_stick_response
+                        .apply(
+                            move_stick_input,
+                        );
+                player_actions.jump_action =
+                    gamepad_buttons
+                        .just_pressed(
+                            GamepadButton::new(
+                                *gamepad,
+                                gamepad_bindings
+                                    .jump_button,
+                            ),
+                        );
+                player_actions
+                    .camera_orbit_action =
+                    camera_stick_input.x
+                        * player_gamepad_parameters
+                            .camera_orbit_speed
+                        * delta_time;
+                // stick up is screen up, which is negative pitch
+                player_actions
+                    .camera_pitch_action =
+                    -camera_stick_input.y
+                        * player_gamepad_parameters
+                            .camera_pitch_speed
+                        * delta_time;
+                player_actions
+                    .camera_zoom_action =
+                    zoom_input
+                        * gamepad_bindings
+                            .camera_zoom_speed
+                        * delta_time;
+
+                let mut fly_camera_vertical_action: f32 = 0.0;
+                if gamepad_buttons.pressed(
+                    GamepadButton::new(
+                        *gamepad,
+                        gamepad_bindings
+                            .fly_camera
+                            .up_button,
+                    ),
+                ) {
+                    fly_camera_vertical_action += 1.0;
+                }
+
+                if gamepad_buttons.pressed(
+                    GamepadButton::new(
+                        *gamepad,
+                        gamepad_bindings
+                            .fly_camera
+                            .down_button,
+                    ),
+                ) {
+                    fly_camera_vertical_action -= 1.0;
+                }
+
+                player_actions
+                    .toggle_fly_camera_action =
+                    gamepad_buttons
+                        .just_pressed(
+                            GamepadButton::new(
+                                *gamepad,
+                                gamepad_bindings
+                                    .fly_camera
+                                    .toggle_button,
+                            ),
+                        );
+                player_actions
+                    .fly_camera_vertical_action =
+                    fly_camera_vertical_action;
+                player_actions
+                    .fly_camera_fast_action =
+                    gamepad_buttons.pressed(
+                        GamepadButton::new(
+                            *gamepad,
+                            gamepad_bindings
+                                .fly_camera
+                                .fast_button,
+                        ),
+                    );
+                player_actions
+                    .fly_camera_slow_action =
+                    gamepad_buttons.pressed(
+                        GamepadButton::new(
+                            *gamepad,
+                            gamepad_bindings
+                                .fly_camera
+                                .slow_button,
+                        ),
+                    );
+            }
+        }
+    }
+}
+
+/// system to update [CharacterPlayerInputComponent] movement using [PlayerActionsComponent].
+/// nb, input is suspended while the player's camera is flying.
+pub fn apply_character_movement_input_using_player_actions_system(
+    player_query: Query<
+        (
+            &PlayerActionsComponent,
+            &PlayerCharacterVariablesComponent,
+            &PlayerCameraModeVariablesComponent,
+        ),
+        With<PlayerTagComponent>,
+    >,
+    mut character_query: Query<
+        (
+            &CharacterTransformationFromPlayerToCameraVariablesComponent,
+            &mut CharacterPlayerInputComponent,
+        ),
+        With<CharacterTagComponent>,
+    >,
+) {
+    for (
+        player_actions,
+        player_character,
+        player_camera_mode,
+    ) in player_query.iter()
+    {
+        let character_result =
+            character_query.get_mut(
+                player_character.entity,
+            );
+
+        if character_result.is_err() {
+            continue;
+        }
+
+        let mut character =
+            character_result.unwrap();
+
+        if player_camera_mode.mode
+            == PlayerCameraMode::Fly
+        {
+            character
+                .1
+                .global_movement_player_input =
+                Vec3::ZERO;
+
+            continue;
+        }
+
+        // screen up is forward
+        let local_input = Vec3::new(
+            player_actions.move_action.x,
+            0.0,
+            -player_actions.move_action.y,
+        );
+
+        let next_input = Affine3A::transform_vector3(
+            &character
+                .0
+                .transformation_from_screen_to_global_on_character_horizontal,
+            local_input,
+        );
+
+        character
+            .1
+            .global_movement_player_input =
+            next_input;
+    }
+}
+
+/// system to update [CharacterPlayerInputComponent] jump using [PlayerActionsComponent].
+/// nb, input is suspended while the player's camera is flying.
+pub fn apply_character_jump_input_using_player_actions_system(
+    player_query: Query<
+        (
+            &PlayerActionsComponent,
+            &PlayerCharacterVariablesComponent,
+            &PlayerCameraModeVariablesComponent,
+        ),
+        With<PlayerTagComponent>,
+    >,
+    mut character_query: Query<
+        (&mut CharacterPlayerInputComponent,),
+        With<CharacterTagComponent>,
+    >,
+) {
+    for (
+        player_actions,
+        player_character,
+        player_camera_mode,
+    ) in player_query.iter()
+    {
+        let character_result =
+            character_query.get_mut(
+                player_character.entity,
+            );
+
+        if character_result.is_err() {
+            continue;
+        }
+
+        let mut character =
+            character_result.unwrap();
+
+        character
+            .0
+            .do_activate_jump_input =
+            player_actions.jump_action
+                && player_camera_mode.mode
+                    == PlayerCameraMode::Actor;
+    }
+}
+
+/// system to update [CameraPlayerInputComponent] using [PlayerActionsComponent].
+pub fn apply_camera_input_using_player_actions_system(
+    input_bindings: Res<
+        InputBindingsResource,
+    >,
+    player_query: Query<
+        (
+            &PlayerActionsComponent,
+            &PlayerCameraVariablesComponent,
+        ),
+        With<PlayerTagComponent>,
+    >,
+    mut camera_query: Query<(
+        &mut CameraPlayerInputComponent,
+        &mut FlyCameraPlayerInputComponent,
+    )>,
+) {
+    for (
+        player_actions,
+        player_camera,
+    ) in player_query.iter()
+    {
+        let camera_result = camera_query
+            .get_mut(player_camera.entity);
+
+        if camera_result.is_err() {
+            continue;
+        }
+
+        let mut camera =
+            camera_result.unwrap();
+
+        camera
+            .0
+            .rotation_player_input =
+            player_actions
+                .camera_orbit_action;
+        camera
+            .0
+            .height_player_input =
+            -player_actions
+                .camera_pitch_action
+                * input_bindings
+                    .camera
+                    .pitch_height_scale;
+        camera
+            .0
+            .lookat_offset_height_player_input =
+            player_actions
+                .camera_pitch_action
+                * input_bindings
+                    .camera
+                    .pitch_lookat_offset_scale;
+        camera
+            .0
+            .distance_player_input =
+            -player_actions
+                .camera_zoom_action;
+
+        // screen up is forward
+        camera
+            .1
+            .movement_player_input =
+            Vec3::new(
+                player_actions.move_action.x,
+                player_actions
+                    .fly_camera_vertical_action,
+                -player_actions.move_action.y,
+            );
+        camera.1.yaw_player_input =
+            player_actions
+                .camera_orbit_action;
+        camera.1.pitch_player_input =
+            player_actions
+                .camera_pitch_action;
+        camera
+            .1
+            .is_fast_player_input =
+            player_actions
+                .fly_camera_fast_action;
+        camera
+            .1
+            .is_slow_player_input =
+            player_actions
+                .fly_camera_slow_action;
+    }
+}
+
+/// system to toggle the camera of each player between actor camera and fly camera using [PlayerActionsComponent].
+/// ie, swap actor camera behaviors for fly camera behaviors, and restore the cylinder coordinates when swapping back.
+pub fn toggle_player_camera_mode_using_player_actions_system(
+    mut commands: Commands,
+    mut player_query: Query<
+        (
+            &PlayerActionsComponent,
+            &PlayerCameraVariablesComponent,
+            &mut PlayerCameraModeVariablesComponent,
+        ),
+        With<PlayerTagComponent>,
+    >,
+    mut camera_query: Query<
+        (
+            &Transform,
+            &mut FlyCameraVariablesComponent,
+            &mut CylinderCoordinatesForDesiredTransformTranslationVariablesComponent,
+        ),
+        With<CameraEyesTagComponent>,
+    >,
+) {
+    for (
+        player_actions,
+        player_camera,
+        mut player_camera_mode,
+    ) in player_query.iter_mut()
+    {
+        if !player_actions
+            .toggle_fly_camera_action
+        {
+            continue;
+        }
+
+        let camera_result = camera_query
+            .get_mut(player_camera.entity);
+
+        if camera_result.is_err() {
+            continue;
+        }
+
+        let mut camera =
+            camera_result.unwrap();
+
+        match player_camera_mode.mode {
+            PlayerCameraMode::Actor => {
+                let (yaw, pitch, _) = camera
+                    .0
+                    .rotation
+                    .to_euler(EulerRot::YXZ);
+
+                camera.1.translation =
+                    camera.0.translation;
+                camera.1.yaw = yaw;
+                camera.1.pitch = pitch;
+                camera
+                    .1
+                    .stored_cylinder_coordinates =
+                    camera
+                        .2
+                        .cylinder_coordindates;
+
+                commands
+                    .entity(player_camera.entity)
+                    .remove::<CylinderActorCameraBehaviorBundle>()
+                    .insert(SetTransformUsingFlyInputBehaviorComponent);
+
+                player_camera_mode.mode =
+                    PlayerCameraMode::Fly;
+            }
+            PlayerCameraMode::Fly => {
+                camera
+                    .2
+                    .cylinder_coordindates =
+                    camera
+                        .1
+                        .stored_cylinder_coordinates;
+
+                commands
+                    .entity(player_camera.entity)
+                    .remove::<SetTransformUsingFlyInputBehaviorComponent>()
+                    .insert(CylinderActorCameraBehaviorBundle::default());
+
+                player_camera_mode.mode =
+                    PlayerCameraMode::Actor;
+            }
+        }
+    }
+}
+
+/// system to update the viewport of the camera of each player, splitting the primary window between all players.
+pub fn apply_player_camera_viewport_system(
+    window_query: Query<
+        &Window,
+        With<PrimaryWindow>,
+    >,
+    player_query: Query<
+        (
+            &PlayerIndexVariablesComponent,
+            &PlayerCameraVariablesComponent,
+        ),
+        With<PlayerTagComponent>,
+    >,
+    mut camera_query: Query<
+        (&mut Camera,),
+        With<CameraEyesTagComponent>,
+    >,
+) {
+    let window_result =
+        window_query.get_single();
+
+    if window_result.is_err() {
+        return;
+    }
+
+    let window = window_result.unwrap();
+
+    let player_count =
+        player_query.iter().len() as u32;
+
+    if player_count == 0 {
+        return;
+    }
+
+    let column_count = f32::ceil(
+        f32::sqrt(player_count as f32),
+    )
+        as u32;
+    let row_count = u32::div_ceil(
+        player_count,
+        column_count,
+    );
+    let viewport_size = UVec2::new(
+        window.physical_width()
+            / column_count,
+        window.physical_height()
+            / row_count,
+    );
+
+    // eg, the window is minimized
+    if viewport_size.x == 0
+        || viewport_size.y == 0
+    {
+        return;
+    }
+
+    for (
+        player_index,
+        player_camera,
+    ) in player_query.iter()
+    {
+        let camera_result = camera_query
+            .get_mut(player_camera.entity);
+
+        if camera_result.is_err() {
+            continue;
+        }
+
+        let mut camera =
+            camera_result.unwrap();
+
+        let viewport_position =
+            UVec2::new(
+                player_index.index
+                    % column_count,
+                player_index.index
+                    / column_count,
+            ) * viewport_size;
+
+        camera.0.order =
+            player_index.index as isize;
+        camera.0.viewport =
+            Some(Viewport {
+                physical_position:
+                    viewport_position,
+                physical_size:
+                    viewport_size,
+                ..Viewport::default()
+            });
+    }
+}
+
+// endregion
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::Vec2;
+
+    use super::StickResponseParameters;
+
+    fn stick_response(
+        response_exponent: f32,
+    ) -> StickResponseParameters {
+        return StickResponseParameters {
+            inner_dead_zone: 0.2,
+            outer_dead_zone: 0.8,
+            response_exponent,
+        };
+    }
+
+    #[test]
+    fn stick_input_within_inner_dead_zone_is_centered(
+    ) {
+        let stick_input = stick_response(1.0)
+            .apply(Vec2::new(0.1, -0.15));
+
+        assert_eq!(stick_input, Vec2::ZERO);
+    }
+
+    #[test]
+    fn stick_input_past_outer_dead_zone_is_fully_tilted(
+    ) {
+        let stick_input = stick_response(1.0)
+            .apply(Vec2::new(0.0, 0.9));
+
+        assert!(stick_input.abs_diff_eq(
+            Vec2::new(0.0, 1.0),
+            1e-6
+        ));
+    }
+
+    #[test]
+    fn stick_input_magnitude_follows_response_exponent(
+    ) {
+        // halfway between the dead zones
+        let stick_input = stick_response(2.0)
+            .apply(Vec2::new(0.5, 0.0));
+
+        assert!(stick_input.abs_diff_eq(
+            Vec2::new(0.25, 0.0),
+            1e-6
+        ));
+    }
+
+    #[test]
+    fn stick_input_direction_is_preserved(
+    ) {
+        let direction =
+            Vec2::new(-3.0, 4.0).normalize();
+        let stick_input = stick_response(2.0)
+            .apply(direction * 0.6);
+
+        assert!(stick_input
+            .normalize()
+            .abs_diff_eq(direction, 1e-6));
+    }
+
+    #[test]
+    fn stick_input_with_overlapping_dead_zones_is_finite(
+    ) {
+        let stick_input =
+            StickResponseParameters {
+                inner_dead_zone: 0.5,
+                outer_dead_zone: 0.5,
+                response_exponent: 1.0,
+            }
+            .apply(Vec2::new(0.6, 0.0));
+
+        assert!(stick_input.is_finite());
+        assert!(stick_input.abs_diff_eq(
+            Vec2::new(1.0, 0.0),
+            1e-6
+        ));
+    }
+}